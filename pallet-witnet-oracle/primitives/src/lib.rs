@@ -0,0 +1,23 @@
+//! The types and functions that the Witnet oracle pallet shares with whatever talks to it from
+//! outside of a runtime, such as relayers.
+//!
+//! They do not depend on Substrate, so that using them does not pull in a whole Substrate stack
+//! next to the one of the client of the parachain.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+use codec::{Decode, Encode};
+use scale_info::TypeInfo;
+
+pub mod protobuf;
+pub mod witnet;
+
+/// Whether Witnet resolved a request successfully, or to an error.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, Debug, TypeInfo)]
+pub enum ResultStatus {
+    Success,
+    /// The code of the RADON error that the request resolved to.
+    Error(u8),
+}
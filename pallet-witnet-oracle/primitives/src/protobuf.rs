@@ -0,0 +1,121 @@
+//! Reading the wire format of protobuf messages, which the bytes of requests are encoded in.
+
+/// Take the next `len` bytes of a protobuf message.
+pub fn take<'a>(input: &mut &'a [u8], len: usize) -> Result<&'a [u8], ()> {
+    if input.len() < len {
+        return Err(());
+    }
+
+    let (head, tail) = input.split_at(len);
+    *input = tail;
+
+    Ok(head)
+}
+
+/// Read the next varint of a protobuf message.
+pub fn read_varint(input: &mut &[u8]) -> Result<u64, ()> {
+    let mut value = 0u64;
+
+    for shift in (0..64).step_by(7) {
+        let (&byte, tail) = input.split_first().ok_or(())?;
+        *input = tail;
+        value |= u64::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+
+    Err(())
+}
+
+/// The values of protobuf fields, which are either numbers or length delimited.
+pub(crate) enum Value<'a> {
+    Number(u64),
+    Bytes(&'a [u8]),
+}
+
+impl<'a> Value<'a> {
+    pub(crate) fn number(self) -> Result<u64, ()> {
+        match self {
+            Value::Number(number) => Ok(number),
+            Value::Bytes(_) => Err(()),
+        }
+    }
+
+    pub(crate) fn bytes(self) -> Result<&'a [u8], ()> {
+        match self {
+            Value::Bytes(bytes) => Ok(bytes),
+            Value::Number(_) => Err(()),
+        }
+    }
+}
+
+/// Go through the fields of a protobuf message, in the order in which they are encoded.
+pub(crate) fn for_each_field<'a>(
+    mut input: &'a [u8],
+    mut f: impl FnMut(u64, Value<'a>) -> Result<(), ()>,
+) -> Result<(), ()> {
+    while !input.is_empty() {
+        let key = read_varint(&mut input)?;
+        let value = match key & 0x07 {
+            // Varint
+            0 => Value::Number(read_varint(&mut input)?),
+            // 64 bits
+            1 => Value::Number(u64::from_le_bytes(
+                take(&mut input, 8)?.try_into().map_err(|_| ())?,
+            )),
+            // Length delimited
+            2 => {
+                let len = usize::try_from(read_varint(&mut input)?).map_err(|_| ())?;
+                Value::Bytes(take(&mut input, len)?)
+            }
+            // 32 bits
+            5 => Value::Number(u64::from(u32::from_le_bytes(
+                take(&mut input, 4)?.try_into().map_err(|_| ())?,
+            ))),
+            // Groups are deprecated, and Witnet does not use them
+            _ => return Err(()),
+        };
+        f(key >> 3, value)?;
+    }
+
+    Ok(())
+}
+
+/// Check that some bytes are a protobuf `DataRequestOutput` that Witnet could make sense of, i.e.
+/// that every message in them is well-formed and made only of the fields that Witnet knows of,
+/// and that their data request has at least one data source.
+///
+/// Text fields may still hold placeholders, so only the structure of the messages is checked.
+pub fn check_data_request_output(bytes: &[u8]) -> Result<(), ()> {
+    let mut data_request = None;
+    for_each_field(bytes, |number, value| match number {
+        1 => {
+            data_request = Some(value.bytes()?);
+            Ok(())
+        }
+        // Witness reward, witnesses, commit and reveal fee, minimum consensus and collateral
+        2..=6 => value.number().map(|_| ()),
+        _ => Err(()),
+    })?;
+
+    let mut sources = 0;
+    for_each_field(data_request.ok_or(())?, |number, value| match number {
+        // Time lock
+        1 => value.number().map(|_| ()),
+        // Data sources, whose fields are checked when the request is sent to Witnet
+        2 => {
+            sources += 1;
+            for_each_field(value.bytes()?, |_, _| Ok(()))
+        }
+        // Aggregation and tally stages
+        3 | 4 => for_each_field(value.bytes()?, |_, _| Ok(())),
+        _ => Err(()),
+    })?;
+
+    if sources == 0 {
+        return Err(());
+    }
+
+    Ok(())
+}
@@ -0,0 +1,267 @@
+//! Making requests understandable to Witnet nodes, and making sense of what they answer.
+//!
+//! Requesters post the protobuf encoding of Witnet `DataRequestOutput` messages, whereas the
+//! `sendRequest` JSON-RPC method of Witnet nodes takes them in the JSON form of
+//! `{"dro": <DataRequestOutput>, "fee": <fee>}`. Unlike protobuf, that JSON form has every field
+//! of every message, so the fields left out by protobuf are filled in with their default values.
+//!
+//! These are shared by the off-chain worker and by the relayer, so that both of them talk to Witnet
+//! nodes in the same way.
+
+use alloc::vec::Vec;
+use core::str;
+
+use crate::{protobuf::for_each_field, ResultStatus};
+
+/// The timestamp of the first checkpoint of the Witnet mainnet, in seconds.
+pub const CHECKPOINT_ZERO_TIMESTAMP: u64 = 1_602_666_000;
+
+/// The time between checkpoints of the Witnet mainnet, in seconds.
+pub const CHECKPOINTS_PERIOD: u64 = 45;
+
+/// Get the timestamp in milliseconds of a Witnet checkpoint, which is what results get
+/// timestamped with.
+pub fn checkpoint_timestamp_millis(checkpoint: u64) -> u64 {
+    CHECKPOINT_ZERO_TIMESTAMP
+        .saturating_add(checkpoint.saturating_mul(CHECKPOINTS_PERIOD))
+        .saturating_mul(1_000)
+}
+
+/// Tell apart RADON errors, which are CBOR arrays tagged with 39 that start with the error code.
+pub fn result_status(result_bytes: &[u8]) -> ResultStatus {
+    match result_bytes {
+        [0xd8, 0x27, 0x81..=0x97, code @ 0x00..=0x17, ..] => ResultStatus::Error(*code),
+        [0xd8, 0x27, 0x81..=0x97, 0x18, code, ..] => ResultStatus::Error(*code),
+        // Errors without a readable code are treated as `Unknown` errors
+        [0xd8, 0x27, ..] => ResultStatus::Error(0),
+        _ => ResultStatus::Success,
+    }
+}
+
+/// Build the JSON parameters of `sendRequest` for the protobuf encoded bytes of a request.
+///
+/// Fails if the bytes are not a valid protobuf `DataRequestOutput`, or if any of its text fields
+/// is not valid UTF-8.
+pub fn send_request_params(bytes: &[u8], fee: u64) -> Result<Vec<u8>, ()> {
+    let mut json = b"{\"dro\":".to_vec();
+    data_request_output(&mut json, bytes)?;
+    json.extend_from_slice(b",\"fee\":");
+    number(&mut json, fee);
+    json.push(b'}');
+
+    Ok(json)
+}
+
+fn data_request_output(json: &mut Vec<u8>, bytes: &[u8]) -> Result<(), ()> {
+    let mut data_request = &[][..];
+    let mut numbers = [0u64; 5];
+    for_each_field(bytes, |number, value| {
+        match number {
+            1 => data_request = value.bytes()?,
+            2..=6 => numbers[number as usize - 2] = value.number()?,
+            _ => {}
+        }
+        Ok(())
+    })?;
+
+    json.extend_from_slice(b"{\"data_request\":");
+    rad_request(json, data_request)?;
+    let names = [
+        "witness_reward",
+        "witnesses",
+        "commit_and_reveal_fee",
+        "min_consensus_percentage",
+        "collateral",
+    ];
+    for (name, value) in names.iter().zip(numbers) {
+        json.extend_from_slice(b",\"");
+        json.extend_from_slice(name.as_bytes());
+        json.extend_from_slice(b"\":");
+        number(json, value);
+    }
+    json.push(b'}');
+
+    Ok(())
+}
+
+fn rad_request(json: &mut Vec<u8>, bytes: &[u8]) -> Result<(), ()> {
+    let mut time_lock = 0;
+    let mut retrieve = Vec::new();
+    let mut aggregate = &[][..];
+    let mut tally = &[][..];
+    for_each_field(bytes, |number, value| {
+        match number {
+            1 => time_lock = value.number()?,
+            2 => retrieve.push(value.bytes()?),
+            3 => aggregate = value.bytes()?,
+            4 => tally = value.bytes()?,
+            _ => {}
+        }
+        Ok(())
+    })?;
+
+    json.extend_from_slice(b"{\"time_lock\":");
+    number(json, time_lock);
+    json.extend_from_slice(b",\"retrieve\":[");
+    for (i, source) in retrieve.into_iter().enumerate() {
+        if i > 0 {
+            json.push(b',');
+        }
+        rad_retrieve(json, source)?;
+    }
+    json.extend_from_slice(b"],\"aggregate\":");
+    rad_reducer(json, aggregate)?;
+    json.extend_from_slice(b",\"tally\":");
+    rad_reducer(json, tally)?;
+    json.push(b'}');
+
+    Ok(())
+}
+
+fn rad_retrieve(json: &mut Vec<u8>, bytes: &[u8]) -> Result<(), ()> {
+    let mut kind = 0;
+    let mut url = &[][..];
+    let mut script = &[][..];
+    let mut body = &[][..];
+    let mut headers = Vec::new();
+    for_each_field(bytes, |number, value| {
+        match number {
+            1 => kind = value.number()?,
+            2 => url = value.bytes()?,
+            3 => script = value.bytes()?,
+            4 => body = value.bytes()?,
+            5 => headers.push(value.bytes()?),
+            _ => {}
+        }
+        Ok(())
+    })?;
+
+    let kind: &[u8] = match kind {
+        0 => b"Unknown",
+        1 => b"HTTP-GET",
+        2 => b"RNG",
+        3 => b"HTTP-POST",
+        _ => return Err(()),
+    };
+    json.extend_from_slice(b"{\"kind\":\"");
+    json.extend_from_slice(kind);
+    json.extend_from_slice(b"\",\"url\":");
+    text(json, url)?;
+    json.extend_from_slice(b",\"script\":");
+    byte_array(json, script);
+    json.extend_from_slice(b",\"body\":");
+    byte_array(json, body);
+    json.extend_from_slice(b",\"headers\":[");
+    for (i, header) in headers.into_iter().enumerate() {
+        let (mut left, mut right) = (&[][..], &[][..]);
+        for_each_field(header, |number, value| {
+            match number {
+                1 => left = value.bytes()?,
+                2 => right = value.bytes()?,
+                _ => {}
+            }
+            Ok(())
+        })?;
+        if i > 0 {
+            json.push(b',');
+        }
+        json.push(b'[');
+        text(json, left)?;
+        json.push(b',');
+        text(json, right)?;
+        json.push(b']');
+    }
+    json.extend_from_slice(b"]}");
+
+    Ok(())
+}
+
+/// Aggregation and tally stages have the same shape: a list of filters and a reducer.
+fn rad_reducer(json: &mut Vec<u8>, bytes: &[u8]) -> Result<(), ()> {
+    let mut filters = Vec::new();
+    let mut reducer = 0;
+    for_each_field(bytes, |number, value| {
+        match number {
+            1 => filters.push(value.bytes()?),
+            2 => reducer = value.number()?,
+            _ => {}
+        }
+        Ok(())
+    })?;
+
+    json.extend_from_slice(b"{\"filters\":[");
+    for (i, filter) in filters.into_iter().enumerate() {
+        let (mut op, mut args) = (0, &[][..]);
+        for_each_field(filter, |number, value| {
+            match number {
+                1 => op = value.number()?,
+                2 => args = value.bytes()?,
+                _ => {}
+            }
+            Ok(())
+        })?;
+        if i > 0 {
+            json.push(b',');
+        }
+        json.extend_from_slice(b"{\"op\":");
+        number(json, op);
+        json.extend_from_slice(b",\"args\":");
+        byte_array(json, args);
+        json.push(b'}');
+    }
+    json.extend_from_slice(b"],\"reducer\":");
+    number(json, reducer);
+    json.push(b'}');
+
+    Ok(())
+}
+
+fn number(json: &mut Vec<u8>, mut value: u64) {
+    let mut digits = [0u8; 20];
+    let mut i = digits.len();
+    loop {
+        i -= 1;
+        digits[i] = b'0' + (value % 10) as u8;
+        value /= 10;
+        if value == 0 {
+            break;
+        }
+    }
+    json.extend_from_slice(&digits[i..]);
+}
+
+/// Byte strings go as arrays of numbers, rather than as strings.
+fn byte_array(json: &mut Vec<u8>, bytes: &[u8]) {
+    json.push(b'[');
+    for (i, byte) in bytes.iter().enumerate() {
+        if i > 0 {
+            json.push(b',');
+        }
+        number(json, u64::from(*byte));
+    }
+    json.push(b']');
+}
+
+fn text(json: &mut Vec<u8>, bytes: &[u8]) -> Result<(), ()> {
+    const DIGITS: &[u8; 16] = b"0123456789abcdef";
+
+    str::from_utf8(bytes).map_err(|_| ())?;
+    json.push(b'"');
+    for &byte in bytes {
+        match byte {
+            b'"' | b'\\' => json.extend_from_slice(&[b'\\', byte]),
+            0x00..=0x1f => json.extend_from_slice(&[
+                b'\\',
+                b'u',
+                b'0',
+                b'0',
+                DIGITS[usize::from(byte >> 4)],
+                DIGITS[usize::from(byte & 0x0f)],
+            ]),
+            _ => json.push(byte),
+        }
+    }
+    json.push(b'"');
+
+    Ok(())
+}
@@ -0,0 +1,33 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+use pallet_witnet_oracle::types::{OperatorInfo, OperatorStats, RequestId};
+use sp_std::vec::Vec;
+
+sp_api::decl_runtime_apis! {
+    /// The API that relayers and dashboards use for querying the state of the Witnet oracle.
+    pub trait WitnetOracleApi<AccountId, BlockNumber, Balance>
+    where
+        AccountId: Codec,
+        BlockNumber: Codec,
+        Balance: Codec,
+    {
+        /// Get the IDs of up to `limit` requests that are still waiting for a result, starting
+        /// right after the `start_after` request ID. IDs come in the order of their `Twox64Concat`
+        /// hashes rather than in numeric order, so `start_after` must be the last ID of the
+        /// previous page rather than the highest one.
+        fn pending_requests(start_after: Option<RequestId>, limit: u32) -> Vec<RequestId>;
+        /// Get the bytes of a request that is still waiting for a result, no matter whether they
+        /// were posted in full or through a template.
+        fn request_bytes(request_id: RequestId) -> Option<Vec<u8>>;
+        /// Get the operator that a request is exclusively assigned to, as long as nobody else can
+        /// report its result yet.
+        fn exclusive_operator(request_id: RequestId) -> Option<AccountId>;
+        /// Get the number of requests that are still waiting for a result.
+        fn pending_requests_count() -> u32;
+        /// Get all the operators, along with their public information.
+        fn operators() -> Vec<(AccountId, OperatorInfo<BlockNumber, Vec<u8>>)>;
+        /// Get the performance statistics of an operator, if it is an operator at all.
+        fn operator_stats(operator: AccountId) -> Option<OperatorStats<BlockNumber, Balance>>;
+    }
+}
@@ -0,0 +1,337 @@
+//! Benchmarks of the calls whose weight depends on the bytes or the arguments that they process,
+//! on the heaviest path that they can take when they succeed, and on the longest one when they
+//! fail.
+//!
+//! Time is assumed to be kept by the timestamp pallet, so that results can be timestamped after
+//! the requests that they resolve.
+
+use frame_benchmarking::{account, benchmarks, impl_benchmark_test_suite};
+use frame_support::{
+    sp_io::hashing::blake2_256,
+    sp_runtime::traits::{Bounded, One, Saturating},
+    sp_std::{vec, vec::Vec},
+    traits::{Currency, Time},
+    BoundedVec,
+};
+use frame_system::RawOrigin;
+use xcm::latest::MultiLocation;
+use xcm_executor::traits::Convert;
+
+use crate::prelude::*;
+
+use super::*;
+
+const SEED: u32 = 0;
+
+/// The template that the requests of the benchmarks pretend to be posted from.
+const TEMPLATE_ID: TemplateId = [7; 32];
+
+fn funded_account<T: Config>(name: &'static str, index: u32) -> T::AccountId {
+    let account = account(name, index, SEED);
+    T::Currency::make_free_balance_be(&account, BalanceFor::<T>::max_value() / 2u32.into());
+
+    account
+}
+
+fn reward<T: Config>() -> BalanceFor<T> {
+    T::Currency::minimum_balance()
+        .max(One::one())
+        .saturating_mul(1_000u32.into())
+}
+
+fn set_time<T: Config + pallet_timestamp::Config>(millis: u32) {
+    pallet_timestamp::Now::<T>::put(<T as pallet_timestamp::Config>::Moment::from(millis));
+}
+
+/// Add a couple of operators, the first of which got the latest assignment, so that picking the
+/// next operator in turn goes through the operator set.
+fn add_operators<T: Config>() -> T::AccountId {
+    let operator: T::AccountId = funded_account::<T>("operator", 0);
+    Operators::<T>::insert(&operator, ());
+    Operators::<T>::insert(funded_account::<T>("operator", 1), ());
+    LastAssignedOperator::<T>::put(&operator);
+
+    operator
+}
+
+/// Fill up the expiry queues of all the blocks where a request posted now could be queued, so
+/// that scheduling its expiry goes through all of them.
+fn fill_expiry_queues<T: Config>() {
+    let full_queue: BoundedVec<RequestId, T::MaxExpiriesPerBlock> =
+        vec![RequestId::MAX; T::MaxExpiriesPerBlock::get() as usize]
+            .try_into()
+            .expect("The queue has as many entries as it can take");
+    let mut at =
+        frame_system::Pallet::<T>::block_number().saturating_add(T::DefaultRequestTimeout::get());
+    for _ in 0..traits::MAX_EXPIRY_SCHEDULING_ATTEMPTS {
+        ExpiryQueue::<T>::insert(at, full_queue.clone());
+        at = at.saturating_add(One::one());
+    }
+}
+
+/// Post a feed request that is assigned to the reporter, that results are sent back to through
+/// XCM, and that counts as posted from a template and as a randomness request, so that reporting
+/// its result touches as much storage as possible.
+fn post_heavy_request<T: Config + pallet_timestamp::Config>(reporter: &T::AccountId) -> RequestId {
+    let requester = funded_account::<T>("requester", 0);
+    let request_id = NextRequestId::<T>::get();
+    set_time::<T>(1_000);
+    Pallet::<T>::post_feed_request(
+        RawOrigin::Signed(requester).into(),
+        reward::<T>(),
+        vec![0; T::MaxRequestSize::get() as usize],
+    )
+    .expect("The request should be posted");
+
+    let until = T::BlockNumber::max_value();
+    Assignments::<T>::insert(request_id, (reporter.clone(), until));
+    Callbacks::<T>::insert(request_id, (MultiLocation::parent(), [0, 0]));
+    RequestTemplates::<T>::insert(request_id, TEMPLATE_ID);
+    PendingTemplateRequests::<T>::insert(TEMPLATE_ID, 1);
+    RandomnessRequests::<T>::insert(request_id, ());
+    set_time::<T>(2_000);
+
+    request_id
+}
+
+fn result_timestamp<T: Config>() -> TimestampFor<T> {
+    T::TimeProvider::now().saturating_sub(One::one())
+}
+
+fn protobuf_field(number: u8, payload: &[u8]) -> Vec<u8> {
+    let mut field = vec![(number << 3) | 2];
+    let mut len = payload.len();
+    while len >= 0x80 {
+        field.push(len as u8 | 0x80);
+        len >>= 7;
+    }
+    field.push(len as u8);
+    field.extend_from_slice(payload);
+    field
+}
+
+/// Build a Witnet data request with a single data source, whose URL has a placeholder for each
+/// argument, and is padded so that the request takes up to `size` bytes.
+fn witnet_request(size: u32, args: u32) -> Vec<u8> {
+    let build = |url_len: usize| {
+        let mut url = b"https://".to_vec();
+        for digit in (b'0'..=b'9').take(args as usize) {
+            url.extend([b'\\', digit, b'\\']);
+        }
+        url.resize(url_len.max(url.len()), b'a');
+        // A `RADRetrieve` with an HTTP GET kind, the URL and a CBOR script
+        let mut retrieve = vec![1 << 3, 1];
+        retrieve.extend(protobuf_field(2, &url));
+        retrieve.extend(protobuf_field(3, &[0x80]));
+        // A `DataRequestOutput` with a `RADRequest` and a witness reward
+        let mut request = protobuf_field(1, &protobuf_field(2, &retrieve));
+        request.extend([2 << 3, 0xe8, 0x07]);
+        request
+    };
+
+    // Shorten the URL by as much as the enclosing fields take, until the request fits
+    let size = size as usize;
+    let mut url_len = size;
+    loop {
+        let request = build(url_len);
+        if request.len() <= size || url_len == 0 {
+            return request;
+        }
+        url_len = url_len.saturating_sub(request.len() - size);
+    }
+}
+
+/// The smallest template that `witnet_request` can build with all the arguments.
+const MIN_TEMPLATE_SIZE: u32 = 64;
+
+benchmarks! {
+    where_clause { where T: pallet_timestamp::Config }
+
+    post_request {
+        let b in 1 .. T::MaxRequestSize::get();
+        let requester = funded_account::<T>("requester", 0);
+        add_operators::<T>();
+        fill_expiry_queues::<T>();
+    }: _(RawOrigin::Signed(requester.clone()), reward::<T>(), vec![0; b as usize])
+    verify {
+        assert_eq!(OpenRequests::<T>::get(&requester), 1);
+    }
+
+    post_request_with_timeout {
+        let b in 1 .. T::MaxRequestSize::get();
+        let requester = funded_account::<T>("requester", 0);
+        add_operators::<T>();
+        fill_expiry_queues::<T>();
+        let timeout = T::DefaultRequestTimeout::get();
+    }: _(RawOrigin::Signed(requester.clone()), reward::<T>(), vec![0; b as usize], timeout)
+    verify {
+        assert_eq!(OpenRequests::<T>::get(&requester), 1);
+    }
+
+    post_remote_request {
+        let b in 1 .. T::MaxRequestSize::get();
+        // The origin is that of the chain that the runtime benchmarks with, whose sovereign account
+        // pays for the request
+        let origin = T::XcmOrigin::successful_origin();
+        let location = T::XcmOrigin::ensure_origin(origin.clone())
+            .expect("The origin should be that of another chain");
+        let requester = T::LocationToAccountId::convert_ref(&location)
+            .expect("The chain should have a sovereign account");
+        T::Currency::make_free_balance_be(&requester, BalanceFor::<T>::max_value() / 2u32.into());
+        add_operators::<T>();
+        fill_expiry_queues::<T>();
+        let reward = reward::<T>().max(T::XcmFee::get());
+    }: {
+        Pallet::<T>::post_remote_request(origin, reward, vec![0; b as usize])
+            .expect("The request should be posted");
+    }
+    verify {
+        assert_eq!(OpenRequests::<T>::get(&requester), 1);
+    }
+
+    post_feed_request {
+        let b in 1 .. T::MaxRequestSize::get();
+        let requester = funded_account::<T>("requester", 0);
+        add_operators::<T>();
+        fill_expiry_queues::<T>();
+    }: _(RawOrigin::Signed(requester.clone()), reward::<T>(), vec![0; b as usize])
+    verify {
+        assert_eq!(OpenRequests::<T>::get(&requester), 1);
+    }
+
+    subscribe {
+        let b in 1 .. T::MaxRequestSize::get();
+        let owner = funded_account::<T>("owner", 0);
+        let subscription_id = NextSubscriptionId::<T>::get();
+        // The queue of the next block has room for a single subscription more
+        let next_block = frame_system::Pallet::<T>::block_number().saturating_add(One::one());
+        let room = T::MaxSubscriptionsPerBlock::get().saturating_sub(1) as usize;
+        let queue: BoundedVec<SubscriptionId, T::MaxSubscriptionsPerBlock> =
+            vec![SubscriptionId::MAX; room]
+                .try_into()
+                .expect("The queue has room for one more subscription");
+        SubscriptionQueue::<T>::insert(next_block, queue);
+        let reward = reward::<T>();
+    }: _(RawOrigin::Signed(owner), reward, reward, One::one(), vec![0; b as usize])
+    verify {
+        assert!(Subscriptions::<T>::contains_key(subscription_id));
+    }
+
+    register_template {
+        let b in MIN_TEMPLATE_SIZE .. T::MaxRequestSize::get();
+        let owner = funded_account::<T>("owner", 0);
+        let template = witnet_request(b, 0);
+        let template_id = blake2_256(&template);
+    }: _(RawOrigin::Signed(owner), template)
+    verify {
+        assert!(Templates::<T>::contains_key(template_id));
+    }
+
+    post_request_from_template {
+        let a in 1 .. templates::MAX_TEMPLATE_ARGS;
+        let b in 0 .. T::MaxRequestSize::get() / 2;
+        let requester = funded_account::<T>("requester", 0);
+        add_operators::<T>();
+        fill_expiry_queues::<T>();
+        // The template is as big as it can be for the filled request to be the biggest one, and
+        // the arguments split the bytes between them
+        let max_size = T::MaxRequestSize::get();
+        let template = witnet_request((max_size + 3 * a).saturating_sub(b).min(max_size), a);
+        let template_id = blake2_256(&template);
+        Pallet::<T>::register_template(RawOrigin::Signed(requester.clone()).into(), template)
+            .expect("The template should be registered");
+        let args: Vec<BoundedVec<u8, T::MaxRequestSize>> = (0..a)
+            .map(|i| vec![b'a'; (b / a + u32::from(i < b % a)) as usize])
+            .map(|arg| arg.try_into().expect("No argument is bigger than a request"))
+            .collect();
+        let args: TemplateArgs<T> = args.try_into().expect("There are not too many arguments");
+    }: _(RawOrigin::Signed(requester.clone()), template_id, reward::<T>(), args)
+    verify {
+        assert_eq!(OpenRequests::<T>::get(&requester), 1);
+    }
+
+    failed_post_request {
+        let b in 1 .. T::MaxRequestSize::get();
+        let requester = funded_account::<T>("requester", 0);
+        // Posting fails once the reward is reserved, right before the storage deposit would be
+        OpenRequests::<T>::insert(&requester, T::MaxOpenRequests::get());
+    }: {
+        let origin = RawOrigin::Signed(requester.clone()).into();
+        let post = Pallet::<T>::post_request(origin, reward::<T>(), vec![0; b as usize]);
+        assert!(post.is_err());
+    }
+    verify {
+        assert_eq!(OpenRequests::<T>::get(&requester), T::MaxOpenRequests::get());
+    }
+
+    report_result {
+        let b in 1 .. T::MaxResultSize::get();
+        let reporter = add_operators::<T>();
+        let request_id = post_heavy_request::<T>(&reporter);
+        let timestamp = result_timestamp::<T>();
+    }: _(
+        RawOrigin::Signed(reporter),
+        request_id,
+        timestamp,
+        [1; 32],
+        vec![0; b as usize],
+        ResultStatus::Success
+    )
+    verify {
+        assert!(!PendingRequests::<T>::contains_key(request_id));
+    }
+
+    report_randomness {
+        let reporter = add_operators::<T>();
+        let request_id = post_heavy_request::<T>(&reporter);
+        let timestamp = result_timestamp::<T>();
+        let mut result_bytes = vec![0x58, 0x20];
+        result_bytes.extend_from_slice(&[1; 32]);
+    }: report_result(
+        RawOrigin::Signed(reporter),
+        request_id,
+        timestamp,
+        [1; 32],
+        result_bytes,
+        ResultStatus::Success
+    )
+    verify {
+        assert!(PendingRandomness::<T>::get().is_some());
+    }
+
+    failed_report_result {
+        let b in 1 .. T::MaxResultSize::get();
+        let reporter = add_operators::<T>();
+        let request_id = post_heavy_request::<T>(&reporter);
+        let timestamp = result_timestamp::<T>();
+        Pallet::<T>::report_result(
+            RawOrigin::Signed(reporter.clone()).into(),
+            request_id,
+            timestamp,
+            [1; 32],
+            vec![0; b as usize],
+            ResultStatus::Success,
+        )
+        .expect("The result should be reported");
+        // Reporting again fails only once the request is read, after checking an assignment to
+        // someone who is no longer an operator
+        let until = T::BlockNumber::max_value();
+        Assignments::<T>::insert(request_id, (account::<T::AccountId>("former", 0, SEED), until));
+    }: {
+        let origin = RawOrigin::Signed(reporter.clone()).into();
+        let bytes = vec![0; b as usize];
+        let report = Pallet::<T>::report_result(
+            origin, request_id, timestamp, [1; 32], bytes, ResultStatus::Success,
+        );
+        assert!(report.is_err());
+    }
+    verify {
+        assert!(Assignments::<T>::contains_key(request_id));
+    }
+}
+
+impl_benchmark_test_suite!(
+    Pallet,
+    crate::mock::ExtBuilder::default().build(),
+    crate::mock::Test
+);
@@ -0,0 +1,23 @@
+//! The keys that operators sign results with, be it in signed transactions or in the payloads of
+//! unsigned ones. `Public` can serve as the `OperatorKey` of runtimes.
+
+use frame_support::sp_runtime::{
+    app_crypto::{app_crypto, sr25519},
+    KeyTypeId, MultiSignature, MultiSigner,
+};
+use frame_system::offchain::AppCrypto;
+
+/// The type of the keys of operators in the keystore.
+pub const KEY_TYPE: KeyTypeId = KeyTypeId(*b"wito");
+
+app_crypto!(sr25519, KEY_TYPE);
+
+/// The operator keys, for signing transactions in runtimes whose accounts are identified by
+/// `MultiSigner`.
+pub struct OperatorId;
+
+impl AppCrypto<MultiSigner, MultiSignature> for OperatorId {
+    type RuntimeAppPublic = Public;
+    type GenericPublic = sr25519::Public;
+    type GenericSignature = sr25519::Signature;
+}
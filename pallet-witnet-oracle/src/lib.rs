@@ -5,17 +5,35 @@ extern crate frame_system;
 extern crate scale_info;
 extern crate sp_runtime;
 
-use frame_support::{pallet_prelude::*, traits::ReservableCurrency};
+use frame_support::{
+    dispatch::PostDispatchInfo,
+    pallet_prelude::*,
+    sp_runtime::{traits::Zero, Percent, RuntimeAppPublic},
+    traits::ReservableCurrency,
+};
 use frame_system::pallet_prelude::*;
 
 pub use pallet::*;
+pub use pallet_witnet_oracle_primitives::{protobuf, witnet};
+use types::{OperatorInfo, OperatorStatsFor, RequestId};
+pub use weights::WeightInfo;
+use xcm::latest::{MultiAsset, MultiLocation, SendXcm};
 
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+pub mod crypto;
+pub mod migrations;
 #[cfg(test)]
 mod mock;
+#[cfg(feature = "ocw")]
+pub mod ocw;
+pub mod randomness;
+pub mod templates;
 #[cfg(test)]
 mod tests;
 pub mod traits;
 pub mod types;
+pub mod weights;
 
 #[frame_support::pallet]
 pub mod pallet {
@@ -33,27 +51,295 @@ pub mod pallet {
 
         /// The maximum number of bytes a data request can take.
         #[pallet::constant]
-        type MaxByteSize: Get<u32>;
+        type MaxRequestSize: Get<u32>;
+
+        /// The maximum number of bytes the result of a data request can take.
+        #[pallet::constant]
+        type MaxResultSize: Get<u32>;
+
+        /// The fixed part of the storage deposit of every request.
+        #[pallet::constant]
+        type RequestDepositBase: Get<BalanceFor<Self>>;
+
+        /// The part of the storage deposit of every request that is proportional to its size.
+        #[pallet::constant]
+        type RequestDepositPerByte: Get<BalanceFor<Self>>;
+
+        /// The maximum number of requests that a single account can have waiting for a result.
+        #[pallet::constant]
+        type MaxOpenRequests: Get<u32>;
+
+        /// The maximum number of operators.
+        #[pallet::constant]
+        type MaxOperators: Get<u32>;
+
+        /// The minimum number of operators, below which operators can no longer be removed.
+        #[pallet::constant]
+        type MinOperators: Get<u32>;
+
+        /// The maximum number of bytes that the name and URL of an operator can take.
+        #[pallet::constant]
+        type MaxOperatorInfoLength: Get<u32>;
+
+        /// The part of the reward that is given back to the requester when a request resolves to
+        /// an error, instead of being paid to the reporter.
+        #[pallet::constant]
+        type ErrorRefund: Get<Percent>;
+
+        /// Whether to keep only the hash of the bytes of results in storage, and publish the full
+        /// bytes through events instead. This applies to the values of feeds as well.
+        #[pallet::constant]
+        type HashOnlyResults: Get<bool>;
 
         /// The trait that will be providing timestamps.
         type TimeProvider: frame_support::traits::Time;
+
+        /// The maximum age that the timestamp of a result can have at the time of reporting.
+        #[pallet::constant]
+        type MaxResultAge: Get<TimestampFor<Self>>;
+
+        /// The number of blocks after which requests expire, unless the requester sets a
+        /// different timeout.
+        #[pallet::constant]
+        type DefaultRequestTimeout: Get<Self::BlockNumber>;
+
+        /// The maximum number of requests that can expire in a single block.
+        #[pallet::constant]
+        type MaxExpiriesPerBlock: Get<u32>;
+
+        /// The origin that is allowed to post requests on behalf of other chains through XCM.
+        type XcmOrigin: EnsureOrigin<Self::Origin, Success = MultiLocation>;
+
+        /// The way to find out the sovereign account of other chains.
+        type LocationToAccountId: xcm_executor::traits::Convert<MultiLocation, Self::AccountId>;
+
+        /// The means for sending results back to the chains that requested them through XCM.
+        type XcmSender: SendXcm;
+
+        /// The pallet and call indexes of the call that receives results on each of the chains that
+        /// can post requests through XCM. Chains without one cannot post requests.
+        type XcmCallbackIndex: sp_runtime::traits::Convert<MultiLocation, Option<[u8; 2]>>;
+
+        /// The part of the reward of a request that pays for sending its result through XCM.
+        #[pallet::constant]
+        type XcmFee: Get<BalanceFor<Self>>;
+
+        /// The account that collects the XCM fees, which should be in charge of topping up the
+        /// sovereign accounts of this chain on other chains.
+        type XcmFeeCollector: Get<Self::AccountId>;
+
+        /// The asset that the sovereign account of this chain on other chains uses for buying
+        /// execution of the callback calls.
+        type XcmFeeAsset: Get<MultiAsset>;
+
+        /// The maximum weight that the call that receives results on other chains can take.
+        #[pallet::constant]
+        type XcmCallbackWeight: Get<Weight>;
+
+        /// The maximum number of subscriptions that can be triggered in a single block.
+        #[pallet::constant]
+        type MaxSubscriptionsPerBlock: Get<u32>;
+
+        /// The origin that is allowed to pause and resume the pallet during an incident.
+        type PauseOrigin: EnsureOrigin<Self::Origin>;
+
+        /// The origin that is allowed to record the disputes over results that operators lose.
+        type DisputeOrigin: EnsureOrigin<Self::Origin>;
+
+        /// The number of blocks without reporting any result after which an operator is
+        /// considered inactive. Zero disables the check.
+        #[pallet::constant]
+        type InactivityPeriod: Get<Self::BlockNumber>;
+
+        /// What to do with the operators that become inactive, e.g. rotating them out.
+        type OnInactiveOperator: traits::OnInactiveOperator<Self>;
+
+        /// The number of blocks during which only the operator that a request gets assigned to
+        /// can report its result. Operators take turns in being assigned requests. Zero disables
+        /// assignments, so that any operator can report any result right away.
+        #[pallet::constant]
+        type ExclusivityWindow: Get<Self::BlockNumber>;
+
+        /// What operators do from their off-chain workers, e.g. relaying requests to a Witnet
+        /// node with `ocw::WitnetNodeRelayer`, which is available under the `ocw` feature.
+        type OffchainRelayer: traits::OffchainRelayer<Self>;
+
+        /// The keys that operators sign results with when reporting them through unsigned
+        /// transactions, so that they need no funded account for paying transaction fees.
+        type OperatorKey: Member + Parameter + RuntimeAppPublic + Ord;
+
+        /// The priority of unsigned transactions reporting results.
+        #[pallet::constant]
+        type UnsignedPriority: Get<TransactionPriority>;
+
+        /// The number of blocks that unsigned transactions reporting results stay valid for.
+        #[pallet::constant]
+        type UnsignedLongevity: Get<TransactionLongevity>;
+
+        /// The maximum number of storage entries that storage migrations go through in a single
+        /// block, so that migrating large storage maps gets spread across several blocks.
+        #[pallet::constant]
+        type MigrationEntriesPerBlock: Get<u32>;
+
+        /// The number of blocks that the randomness of a randomness request waits for once
+        /// reported, before it gets mixed with the hash of the parent block and handed out. With
+        /// two blocks or more, that hash is unknown to the operator that reports the randomness.
+        #[pallet::constant]
+        type RandomnessDelay: Get<Self::BlockNumber>;
+
+        /// The weights of the calls, which should be derived from the benchmarks of the pallet.
+        type WeightInfo: WeightInfo;
     }
 
+    /// The current storage version.
+    const STORAGE_VERSION: StorageVersion = StorageVersion::new(4);
+
     #[pallet::pallet]
     #[pallet::generate_store(pub(super) trait Store)]
+    #[pallet::storage_version(STORAGE_VERSION)]
     pub struct Pallet<T>(_);
 
     #[pallet::storage]
     pub(super) type Requests<T: Config> =
         StorageMap<_, Twox64Concat, RequestId, RequestEntry<T>, OptionQuery>;
 
+    #[pallet::storage]
+    pub(super) type ResultErrors<T: Config> =
+        StorageMap<_, Twox64Concat, RequestId, u8, OptionQuery>;
+
+    #[pallet::storage]
+    pub(super) type ResultHashes<T: Config> =
+        StorageMap<_, Twox64Concat, RequestId, [u8; 32], OptionQuery>;
+
+    #[pallet::storage]
+    pub(super) type Deposits<T: Config> =
+        StorageMap<_, Twox64Concat, RequestId, (T::AccountId, BalanceFor<T>), OptionQuery>;
+
+    #[pallet::storage]
+    pub(super) type OpenRequests<T: Config> =
+        StorageMap<_, Twox64Concat, T::AccountId, u32, ValueQuery>;
+
+    #[pallet::storage]
+    pub(super) type PendingRequests<T: Config> =
+        CountedStorageMap<_, Twox64Concat, RequestId, (), OptionQuery>;
+
+    #[pallet::storage]
+    pub(super) type PostedAt<T: Config> =
+        StorageMap<_, Twox64Concat, RequestId, (T::BlockNumber, TimestampFor<T>), OptionQuery>;
+
+    #[pallet::storage]
+    pub(super) type Deadlines<T: Config> =
+        StorageMap<_, Twox64Concat, RequestId, T::BlockNumber, OptionQuery>;
+
+    #[pallet::storage]
+    pub(super) type ExpiryQueue<T: Config> = StorageMap<
+        _,
+        Twox64Concat,
+        T::BlockNumber,
+        BoundedVec<RequestId, T::MaxExpiriesPerBlock>,
+        ValueQuery,
+    >;
+
+    #[pallet::storage]
+    pub(super) type Assignments<T: Config> =
+        StorageMap<_, Twox64Concat, RequestId, (T::AccountId, T::BlockNumber), OptionQuery>;
+
+    #[pallet::storage]
+    pub(super) type LastAssignedOperator<T: Config> = StorageValue<_, T::AccountId, OptionQuery>;
+
+    #[pallet::storage]
+    pub(super) type Callbacks<T: Config> =
+        StorageMap<_, Twox64Concat, RequestId, XcmCallback, OptionQuery>;
+
+    #[pallet::storage]
+    pub(super) type Consumers<T: Config> =
+        StorageMap<_, Twox64Concat, RequestId, T::AccountId, OptionQuery>;
+
+    #[pallet::storage]
+    pub(super) type ConsumedResults<T: Config> =
+        StorageMap<_, Twox64Concat, RequestId, (), OptionQuery>;
+
+    #[pallet::storage]
+    pub(super) type PausedOperations<T> = StorageValue<_, PauseFlags, ValueQuery>;
+
+    #[pallet::storage]
+    pub(super) type DeferredPayouts<T: Config> =
+        StorageMap<_, Twox64Concat, RequestId, DeferredPayout<T>, OptionQuery>;
+
     #[pallet::storage]
     pub(super) type Operators<T: Config> =
-        StorageMap<_, Twox64Concat, T::AccountId, (), OptionQuery>;
+        CountedStorageMap<_, Twox64Concat, T::AccountId, (), OptionQuery>;
+
+    #[pallet::storage]
+    pub(super) type OperatorDetails<T: Config> =
+        StorageMap<_, Twox64Concat, T::AccountId, OperatorInfoFor<T>, OptionQuery>;
+
+    #[pallet::storage]
+    pub(super) type OperatorPerformance<T: Config> =
+        StorageMap<_, Twox64Concat, T::AccountId, OperatorStatsFor<T>, ValueQuery>;
+
+    #[pallet::storage]
+    pub(super) type OperatorKeys<T: Config> =
+        StorageMap<_, Twox64Concat, T::AccountId, T::OperatorKey, OptionQuery>;
 
     #[pallet::storage]
     pub(super) type NextRequestId<T> = StorageValue<_, RequestId, ValueQuery>;
 
+    #[pallet::storage]
+    pub(super) type Templates<T: Config> =
+        StorageMap<_, Identity, TemplateId, TemplateEntry<T>, OptionQuery>;
+
+    #[pallet::storage]
+    pub(super) type RequestTemplates<T: Config> =
+        StorageMap<_, Twox64Concat, RequestId, TemplateId, OptionQuery>;
+
+    /// The number of requests posted from each template that are still waiting for a result,
+    /// which keep the template from being unregistered.
+    #[pallet::storage]
+    pub(super) type PendingTemplateRequests<T: Config> =
+        StorageMap<_, Identity, TemplateId, u32, ValueQuery>;
+
+    #[pallet::storage]
+    pub(super) type Subscriptions<T: Config> =
+        StorageMap<_, Twox64Concat, SubscriptionId, Subscription<T>, OptionQuery>;
+
+    #[pallet::storage]
+    pub(super) type SubscriptionQueue<T: Config> = StorageMap<
+        _,
+        Twox64Concat,
+        T::BlockNumber,
+        BoundedVec<SubscriptionId, T::MaxSubscriptionsPerBlock>,
+        ValueQuery,
+    >;
+
+    #[pallet::storage]
+    pub(super) type NextSubscriptionId<T> = StorageValue<_, SubscriptionId, ValueQuery>;
+
+    #[pallet::storage]
+    pub(super) type Feeds<T: Config> = StorageMap<_, Identity, FeedId, FeedEntry<T>, OptionQuery>;
+
+    #[pallet::storage]
+    pub(super) type RequestFeeds<T: Config> =
+        StorageMap<_, Twox64Concat, RequestId, FeedId, OptionQuery>;
+
+    #[pallet::storage]
+    pub(super) type RandomnessRequests<T: Config> =
+        StorageMap<_, Twox64Concat, RequestId, (), OptionQuery>;
+
+    #[pallet::storage]
+    pub(super) type LatestRandomness<T: Config> =
+        StorageValue<_, ([u8; 32], T::BlockNumber), OptionQuery>;
+
+    /// The latest randomness that has been reported, along with its request and the block where it
+    /// was reported, until it is old enough to be handed out.
+    #[pallet::storage]
+    pub(super) type PendingRandomness<T: Config> =
+        StorageValue<_, (RequestId, [u8; 32], T::BlockNumber), OptionQuery>;
+
+    /// How far the storage migration that is spread across several blocks has got, if any.
+    #[pallet::storage]
+    pub(super) type OngoingMigration<T: Config> = StorageValue<_, MigrationCursor, OptionQuery>;
+
     #[pallet::genesis_config]
     pub struct GenesisConfig<T: Config> {
         pub operators: Vec<T::AccountId>,
@@ -77,8 +363,14 @@ pub mod pallet {
     #[pallet::genesis_build]
     impl<T: Config> GenesisBuild<T> for GenesisConfig<T> {
         fn build(&self) {
+            assert!(
+                self.operators.len() <= T::MaxOperators::get() as usize,
+                "Too many operators in the genesis config"
+            );
+
             for operator in &self.operators {
                 <Operators<T>>::insert(operator, ());
+                traits::note_operator_joined::<T>(operator);
             }
         }
     }
@@ -90,12 +382,53 @@ pub mod pallet {
         PostedRequest {
             request_id: RequestId,
             requester: T::AccountId,
+            assigned_operator: Option<T::AccountId>,
         },
         /// A request has been resolved.
         PostedResult {
             request_id: RequestId,
             reporter: T::AccountId,
         },
+        /// The full bytes of a result, which only its hash is stored of.
+        PublishedResult {
+            request_id: RequestId,
+            result_bytes: Vec<u8>,
+        },
+        /// A request has resolved to an error, and part of its reward has been given back.
+        ReportedError {
+            request_id: RequestId,
+            error_code: u8,
+        },
+        /// The result of a request has been sent through XCM to another chain.
+        DeliveredResult {
+            request_id: RequestId,
+            destination: MultiLocation,
+        },
+        /// The result of a request could not be sent through XCM to another chain.
+        FailedResultDelivery {
+            request_id: RequestId,
+            destination: MultiLocation,
+        },
+        /// A request has expired before being resolved, and its reward has been given back.
+        ExpiredRequest { request_id: RequestId },
+        /// A request has been removed from storage, and its deposit has been given back.
+        PrunedRequest { request_id: RequestId },
+        /// A request, along with its result and its storage deposit, has changed hands.
+        TransferredRequest {
+            request_id: RequestId,
+            from: T::AccountId,
+            to: T::AccountId,
+        },
+        /// The result of a request can only be read or consumed by a specific account, if any.
+        SetConsumer {
+            request_id: RequestId,
+            consumer: Option<T::AccountId>,
+        },
+        /// The result of a request has been consumed, so it can no longer be read.
+        ConsumedResult {
+            request_id: RequestId,
+            consumer: T::AccountId,
+        },
         /// A new operator has been added.
         AddedOperator {
             added_operator: T::AccountId,
@@ -106,6 +439,50 @@ pub mod pallet {
             removed_operator: T::AccountId,
             removed_by: T::AccountId,
         },
+        /// Some operations of the pallet have been paused.
+        Paused { operations: PauseFlags },
+        /// Some operations of the pallet have been resumed.
+        Unpaused { operations: PauseFlags },
+        /// The payout of a result, which had been deferred while payouts were paused, has been made.
+        ClaimedPayout { request_id: RequestId },
+        /// An operator has been removed for not reporting any result for too long.
+        RemovedInactiveOperator { operator: T::AccountId },
+        /// An operator has lost a dispute over one of its results.
+        LostDispute { operator: T::AccountId },
+        /// An operator has updated its public information.
+        UpdatedOperatorInfo { operator: T::AccountId },
+        /// An operator has set the key that it signs unsigned reports of results with.
+        UpdatedOperatorKey { operator: T::AccountId },
+        /// A new request template has been registered.
+        RegisteredTemplate {
+            template_id: TemplateId,
+            owner: T::AccountId,
+        },
+        /// A request template has been unregistered, and its storage deposit given back.
+        UnregisteredTemplate { template_id: TemplateId },
+        /// A new subscription has been created.
+        Subscribed {
+            subscription_id: SubscriptionId,
+            owner: T::AccountId,
+        },
+        /// A subscription has posted a new request.
+        TriggeredSubscription {
+            subscription_id: SubscriptionId,
+            request_id: RequestId,
+        },
+        /// A subscription has been cancelled by its owner.
+        CancelledSubscription { subscription_id: SubscriptionId },
+        /// The latest value of a feed has been updated.
+        UpdatedFeed {
+            feed_id: FeedId,
+            request_id: RequestId,
+        },
+        /// The latest randomness has been updated with the result of a randomness request, which
+        /// is handed out from now on.
+        UpdatedRandomness { request_id: RequestId },
+        /// A subscription has come to an end because it ran out of budget, could not be
+        /// rescheduled, or its request no longer fits in `MaxRequestSize`.
+        ExhaustedSubscription { subscription_id: SubscriptionId },
     }
 
     /// Error for the Witnet pallet.
@@ -119,51 +496,503 @@ pub mod pallet {
         UnknownRequest,
         /// The signer of the transaction is not an operator.
         UnauthorizedOperator,
+        /// The request is still exclusively assigned to a different operator.
+        NotAssignedOperator,
         /// Reported an empty byte array as the result to a request.
         EmptyResult,
         /// A result is too big or overly complex.
         OversizedResult,
         /// The reported timestamp comes from the future.
         ResultFromFuture,
+        /// The reported timestamp is older than the request itself.
+        ResultPredatesRequest,
+        /// The reported timestamp is older than the maximum result age.
+        ResultTooOld,
+        /// The result of this request has not been reported yet.
+        UnresolvedRequest,
+        /// The bytes do not match those of the result that was reported.
+        ResultMismatch,
         /// The result of this request had already been reported.
         AlreadyReported,
+        /// The request expired before its result was reported.
+        RequestExpired,
+        /// Tried to prune a request that is still waiting for a result.
+        RequestStillPending,
+        /// The requester already has too many requests waiting for a result.
+        TooManyOpenRequests,
+        /// The timeout of a request cannot be zero.
+        InvalidTimeout,
+        /// The deadline of the request has not passed yet.
+        RequestNotDue,
+        /// The sovereign account of the chain posting a request through XCM is unknown.
+        UnknownSovereignAccount,
+        /// The signer of the transaction is not the requester of the request.
+        NotRequester,
+        /// The result of this request is reserved to a different consumer.
+        NotConsumer,
+        /// The result of this request has already been consumed.
+        ResultConsumed,
+        /// The reward of a request does not cover the XCM fees for sending its result.
+        UnderpayingCallback,
+        /// The destination of a callback cannot be converted into a known location.
+        BadCallbackDestination,
         /// An operator is trying to add themself as operator.
         OperatorSelfAddition,
         /// An operator is trying to remove themself as operator.
         OperatorSelfRemoval,
         /// The specific operator account id is unknown.
         UnknownOperator,
+        /// There is no room left for more operators.
+        TooManyOperators,
+        /// Removing the operator would leave too few operators.
+        TooFewOperators,
+        /// The name or URL of an operator is too long.
+        OversizedOperatorInfo,
+        /// The period of a subscription cannot be zero.
+        InvalidSubscriptionPeriod,
+        /// The budget of a subscription does not cover a single request.
+        InsufficientSubscriptionBudget,
+        /// Tried to follow up on a subscription ID that does not exist.
+        UnknownSubscription,
+        /// The signer of the transaction is not the owner of the subscription.
+        NotSubscriptionOwner,
+        /// There is no room left for scheduling more subscriptions.
+        SubscriptionQueueFull,
+        /// A template with the very same bytes has already been registered.
+        TemplateAlreadyRegistered,
+        /// Tried to post a request from a template that was never registered.
+        UnknownTemplate,
+        /// The signer of the transaction is not the owner of the template.
+        NotTemplateOwner,
+        /// Some request posted from the template is still waiting for a result.
+        TemplateInUse,
+        /// The template is not a valid Witnet data request.
+        InvalidTemplate,
+        /// The arguments do not fill the placeholders of the template.
+        InvalidTemplateArguments,
+        /// Tried to read a feed that has never been updated.
+        UnknownFeed,
+        /// The latest value of a feed is older than acceptable.
+        StaleFeed,
+        /// The operation has been paused.
+        Paused,
+        /// There is no deferred payout for this request.
+        UnknownPayout,
+        /// The storage deposit of the request cannot be moved to the new owner in full.
+        DepositNotTransferred,
+        /// The result of this request goes to a feed or back to another chain, so it cannot be
+        /// reserved to a consumer.
+        UnreservableResult,
+    }
+
+    #[pallet::hooks]
+    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+        fn on_initialize(now: T::BlockNumber) -> Weight {
+            // Expire all the requests whose deadline is this block
+            let mut weight = traits::expire_requests::<T>(now);
+
+            // Trigger all the subscriptions that are due in this block
+            let due = SubscriptionQueue::<T>::take(now);
+            weight = weight.saturating_add(T::DbWeight::get().reads_writes(1, 1));
+            for subscription_id in due {
+                weight =
+                    weight.saturating_add(traits::trigger_subscription::<T>(subscription_id, now));
+            }
+
+            // Hand out the latest randomness once it is old enough
+            weight = weight.saturating_add(traits::release_randomness::<T>(now));
+
+            // Look for inactive operators once in every inactivity period
+            let period = T::InactivityPeriod::get();
+            if !period.is_zero() && (now % period).is_zero() {
+                weight = weight.saturating_add(traits::check_operators_activity::<T>(now));
+            }
+
+            // Carry on with the ongoing storage migration, if any
+            weight = weight.saturating_add(migrations::migrate_next_entries::<T>());
+
+            weight
+        }
+
+        fn on_runtime_upgrade() -> Weight {
+            migrations::migrate::<T>()
+        }
+
+        fn offchain_worker(now: T::BlockNumber) {
+            T::OffchainRelayer::relay(now);
+        }
     }
 
     #[pallet::call]
     impl<T: Config> Pallet<T> {
-        #[pallet::weight(50_000_000)]
+        #[pallet::weight(traits::post_request_weight::<T>(T::MaxRequestSize::get() as usize))]
         pub fn post_request(
             origin: OriginFor<T>,
             reward: BalanceFor<T>,
             bytes: Vec<u8>,
-        ) -> DispatchResult {
-            <Pallet<T> as traits::WitnetOracle<T, OriginFor<T>>>::post_request(
+        ) -> DispatchResultWithPostInfo {
+            let len = bytes.len();
+            let post = <Pallet<T> as traits::WitnetOracle<T, OriginFor<T>>>::post_request(
                 origin, reward, bytes,
+            );
+
+            traits::with_actual_weight(
+                post,
+                traits::post_request_weight::<T>(len),
+                traits::failed_post_request_weight::<T>(len),
             )
         }
 
-        #[pallet::weight(25_000_000)]
+        #[pallet::weight(traits::report_result_weight::<T>(T::MaxResultSize::get() as usize))]
         pub fn report_result(
             origin: OriginFor<T>,
             request_id: u64,
             timestamp: TimestampFor<T>,
             dr_tx_hash: [u8; 32],
             result_bytes: Vec<u8>,
-        ) -> DispatchResult {
-            <Pallet<T> as traits::WitnetOracle<T, OriginFor<T>>>::report_result(
+            status: ResultStatus,
+        ) -> DispatchResultWithPostInfo {
+            let len = result_bytes.len();
+            let report = <Pallet<T> as traits::WitnetOracle<T, OriginFor<T>>>::report_result(
                 origin,
                 request_id,
                 timestamp,
                 dr_tx_hash,
                 result_bytes,
+                status,
+            );
+            let info = traits::with_actual_weight(
+                report,
+                traits::report_result_weight::<T>(len),
+                traits::failed_report_result_weight::<T>(len),
+            )?;
+
+            // Successful reports are free, so that honest relaying costs nothing, while failing
+            // reports still pay, so that spamming does not
+            Ok(PostDispatchInfo {
+                pays_fee: Pays::No,
+                ..info
+            })
+        }
+
+        #[pallet::weight(traits::post_request_with_timeout_weight::<T>(bytes.len()))]
+        pub fn post_request_with_timeout(
+            origin: OriginFor<T>,
+            reward: BalanceFor<T>,
+            bytes: Vec<u8>,
+            timeout: T::BlockNumber,
+        ) -> DispatchResult {
+            <Pallet<T> as traits::WitnetOracle<T, OriginFor<T>>>::post_request_with_timeout(
+                origin, reward, bytes, timeout,
+            )
+        }
+
+        #[pallet::weight(traits::post_remote_request_weight::<T>(bytes.len()))]
+        pub fn post_remote_request(
+            origin: OriginFor<T>,
+            reward: BalanceFor<T>,
+            bytes: Vec<u8>,
+        ) -> DispatchResult {
+            // Ensure that the request comes from another chain, and get hold of its location
+            let location = T::XcmOrigin::ensure_origin(origin)?;
+
+            traits::post_remote_request::<T>(location, reward, bytes)
+        }
+
+        #[pallet::weight(traits::post_feed_request_weight::<T>(bytes.len()))]
+        pub fn post_feed_request(
+            origin: OriginFor<T>,
+            reward: BalanceFor<T>,
+            bytes: Vec<u8>,
+        ) -> DispatchResult {
+            <Pallet<T> as traits::WitnetOracle<T, OriginFor<T>>>::post_feed_request(
+                origin, reward, bytes,
+            )
+        }
+
+        #[pallet::weight(25_000_000)]
+        pub fn prune_request(origin: OriginFor<T>, request_id: RequestId) -> DispatchResult {
+            <Pallet<T> as traits::WitnetOracle<T, OriginFor<T>>>::prune_request(origin, request_id)
+        }
+
+        #[pallet::weight(25_000_000)]
+        pub fn set_operator_info(
+            origin: OriginFor<T>,
+            name: Vec<u8>,
+            witnet_pkh: [u8; 20],
+            url: Vec<u8>,
+        ) -> DispatchResult {
+            <Pallet<T> as traits::WitnetOracle<T, OriginFor<T>>>::set_operator_info(
+                origin, name, witnet_pkh, url,
+            )
+        }
+
+        #[pallet::weight(traits::subscribe_weight::<T>(bytes.len()))]
+        pub fn subscribe(
+            origin: OriginFor<T>,
+            reward: BalanceFor<T>,
+            budget: BalanceFor<T>,
+            period: T::BlockNumber,
+            bytes: Vec<u8>,
+        ) -> DispatchResult {
+            <Pallet<T> as traits::WitnetOracle<T, OriginFor<T>>>::subscribe(
+                origin, reward, budget, period, bytes,
+            )
+        }
+
+        #[pallet::weight(25_000_000)]
+        pub fn cancel_subscription(
+            origin: OriginFor<T>,
+            subscription_id: SubscriptionId,
+        ) -> DispatchResult {
+            <Pallet<T> as traits::WitnetOracle<T, OriginFor<T>>>::cancel_subscription(
+                origin,
+                subscription_id,
+            )
+        }
+
+        #[pallet::weight(25_000_000)]
+        pub fn record_lost_dispute(origin: OriginFor<T>, operator: T::AccountId) -> DispatchResult {
+            // Ensure that the dispute has been settled by the right authority
+            T::DisputeOrigin::ensure_origin(origin)?;
+
+            traits::record_lost_dispute::<T>(operator)
+        }
+
+        #[pallet::weight(traits::register_template_weight::<T>(bytes.len()))]
+        pub fn register_template(origin: OriginFor<T>, bytes: Vec<u8>) -> DispatchResult {
+            <Pallet<T> as traits::WitnetOracle<T, OriginFor<T>>>::register_template(origin, bytes)
+        }
+
+        #[pallet::weight(25_000_000)]
+        pub fn unregister_template(
+            origin: OriginFor<T>,
+            template_id: TemplateId,
+        ) -> DispatchResult {
+            <Pallet<T> as traits::WitnetOracle<T, OriginFor<T>>>::unregister_template(
+                origin,
+                template_id,
+            )
+        }
+
+        #[pallet::weight(traits::post_request_from_template_weight::<T>(args))]
+        pub fn post_request_from_template(
+            origin: OriginFor<T>,
+            template_id: TemplateId,
+            reward: BalanceFor<T>,
+            args: TemplateArgs<T>,
+        ) -> DispatchResult {
+            <Pallet<T> as traits::WitnetOracle<T, OriginFor<T>>>::post_request_from_template(
+                origin,
+                template_id,
+                reward,
+                args,
             )
         }
+
+        #[pallet::weight(10_000_000)]
+        pub fn pause(origin: OriginFor<T>, operations: PauseFlags) -> DispatchResult {
+            // Ensure that the pause has been called by the right authority
+            T::PauseOrigin::ensure_origin(origin)?;
+
+            traits::set_paused::<T>(operations, true);
+
+            Ok(())
+        }
+
+        #[pallet::weight(10_000_000)]
+        pub fn unpause(origin: OriginFor<T>, operations: PauseFlags) -> DispatchResult {
+            // Ensure that the pause has been lifted by the right authority
+            T::PauseOrigin::ensure_origin(origin)?;
+
+            traits::set_paused::<T>(operations, false);
+
+            Ok(())
+        }
+
+        #[pallet::weight(25_000_000)]
+        pub fn claim_payout(origin: OriginFor<T>, request_id: RequestId) -> DispatchResult {
+            // Anyone can claim a payout on behalf of the reporter
+            ensure_signed(origin)?;
+
+            traits::claim_payout::<T>(request_id)
+        }
+
+        #[pallet::weight(25_000_000)]
+        pub fn expire_request(origin: OriginFor<T>, request_id: RequestId) -> DispatchResult {
+            // Anyone can expire an overdue request on behalf of its requester
+            ensure_signed(origin)?;
+
+            traits::expire_overdue_request::<T>(request_id)
+        }
+
+        #[pallet::weight(10_000_000)]
+        pub fn set_operator_key(origin: OriginFor<T>, key: T::OperatorKey) -> DispatchResult {
+            <Pallet<T> as traits::WitnetOracle<T, OriginFor<T>>>::set_operator_key(origin, key)
+        }
+
+        #[pallet::weight(traits::report_result_weight::<T>(payload.result_bytes.len()))]
+        pub fn report_result_unsigned(
+            origin: OriginFor<T>,
+            payload: ResultPayload<T>,
+            _signature: OperatorSignatureFor<T>,
+        ) -> DispatchResultWithPostInfo {
+            // The signature of the payload has already been checked in `validate_unsigned`
+            ensure_none(origin)?;
+
+            let len = payload.result_bytes.len();
+            let report = traits::report_result_as::<T>(
+                payload.operator,
+                payload.request_id,
+                payload.timestamp,
+                payload.dr_tx_hash,
+                payload.result_bytes,
+                payload.status,
+            );
+
+            traits::with_actual_weight(
+                report,
+                traits::report_result_weight::<T>(len),
+                traits::failed_report_result_weight::<T>(len),
+            )
+        }
+
+        #[pallet::weight(25_000_000)]
+        pub fn transfer_request(
+            origin: OriginFor<T>,
+            request_id: RequestId,
+            new_owner: T::AccountId,
+        ) -> DispatchResult {
+            <Pallet<T> as traits::WitnetOracle<T, OriginFor<T>>>::transfer_request(
+                origin, request_id, new_owner,
+            )
+        }
+
+        #[pallet::weight(10_000_000)]
+        pub fn set_consumer(
+            origin: OriginFor<T>,
+            request_id: RequestId,
+            consumer: Option<T::AccountId>,
+        ) -> DispatchResult {
+            <Pallet<T> as traits::WitnetOracle<T, OriginFor<T>>>::set_consumer(
+                origin, request_id, consumer,
+            )
+        }
+
+        #[pallet::weight(traits::post_request_weight::<T>(randomness::RANDOMNESS_REQUEST.len()))]
+        pub fn request_randomness(origin: OriginFor<T>, reward: BalanceFor<T>) -> DispatchResult {
+            <Pallet<T> as traits::WitnetOracle<T, OriginFor<T>>>::request_randomness(origin, reward)
+        }
+    }
+
+    #[pallet::validate_unsigned]
+    impl<T: Config> ValidateUnsigned for Pallet<T> {
+        type Call = Call<T>;
+
+        fn validate_unsigned(_source: TransactionSource, call: &Self::Call) -> TransactionValidity {
+            match call {
+                Call::report_result_unsigned { payload, signature } => {
+                    traits::validate_unsigned_result::<T>(payload, signature)
+                }
+                _ => InvalidTransaction::Call.into(),
+            }
+        }
+    }
+}
+
+impl<T: Config> Pallet<T> {
+    /// Get the IDs of up to `limit` requests that are still waiting for a result.
+    ///
+    /// Pages are linked through `start_after`, which is the last ID of the previous page. IDs come
+    /// in storage order, i.e. in the order of their `Twox64Concat` hashes, which is stable but
+    /// unrelated to the order in which the requests were posted, so the highest ID of a page is
+    /// not a valid `start_after`.
+    pub fn pending_requests(start_after: Option<RequestId>, limit: u32) -> Vec<RequestId> {
+        let limit = limit as usize;
+        match start_after {
+            Some(request_id) => {
+                let starting_key = PendingRequests::<T>::hashed_key_for(request_id);
+                PendingRequests::<T>::iter_keys_from(starting_key)
+                    .take(limit)
+                    .collect()
+            }
+            None => PendingRequests::<T>::iter_keys().take(limit).collect(),
+        }
+    }
+
+    /// Get the bytes of a request that is still waiting for a result, no matter whether they were
+    /// posted in full or through a template.
+    pub fn request_bytes(request_id: RequestId) -> Option<Vec<u8>> {
+        let (bytes, _) = Requests::<T>::get(request_id)?.0?;
+
+        match RequestTemplates::<T>::get(request_id) {
+            Some(template_id) => {
+                Templates::<T>::get(template_id).map(|(_, bytes, _)| bytes.into_inner())
+            }
+            None => Some(bytes.into_inner()),
+        }
+    }
+
+    /// Get the operator that a request is exclusively assigned to, as long as its exclusivity
+    /// window is still open and that operator has not been removed since. Nobody else can report
+    /// the result of the request until then.
+    pub fn exclusive_operator(request_id: RequestId) -> Option<T::AccountId> {
+        let (operator, until) = Assignments::<T>::get(request_id)?;
+        let block = frame_system::Pallet::<T>::block_number();
+
+        if block < until && Operators::<T>::contains_key(&operator) {
+            Some(operator)
+        } else {
+            None
+        }
+    }
+
+    /// Get the number of requests that are still waiting for a result.
+    pub fn pending_requests_count() -> u32 {
+        PendingRequests::<T>::count()
+    }
+
+    /// Get the current owner of a request and its result, which is the requester unless the request
+    /// has been transferred since. Results reported before owners were kept around have no owner
+    /// if their requests were posted before there were storage deposits.
+    pub fn request_owner(request_id: RequestId) -> Option<T::AccountId> {
+        Requests::<T>::get(request_id)?.2
+    }
+
+    /// Get the code of the error that a request resolved to, if it did not resolve successfully.
+    pub fn result_error(request_id: RequestId) -> Option<u8> {
+        ResultErrors::<T>::get(request_id)
+    }
+
+    /// Get the number of operators.
+    pub fn operators_count() -> u32 {
+        Operators::<T>::count()
+    }
+
+    /// Get all the operators, along with their public information.
+    pub fn operators() -> Vec<(T::AccountId, OperatorInfo<T::BlockNumber, Vec<u8>>)> {
+        Operators::<T>::iter_keys()
+            .map(|operator| {
+                let details = OperatorDetails::<T>::get(&operator).unwrap_or_default();
+                let info = OperatorInfo {
+                    name: details.name.into_inner(),
+                    witnet_pkh: details.witnet_pkh,
+                    url: details.url.into_inner(),
+                    joined_at: details.joined_at,
+                };
+
+                (operator, info)
+            })
+            .collect()
+    }
+
+    /// Get the performance statistics of an operator.
+    pub fn operator_stats(operator: &T::AccountId) -> Option<OperatorStatsFor<T>> {
+        if !Operators::<T>::contains_key(operator) {
+            return None;
+        }
+
+        Some(OperatorPerformance::<T>::get(operator))
     }
 }
 
@@ -0,0 +1,290 @@
+use frame_support::{
+    codec::FullCodec,
+    sp_io::hashing::blake2_256,
+    storage::{unhashed, IterableStorageMap, StorageMap as StorageMapT},
+    traits::{GetStorageVersion, StorageVersion},
+};
+
+use super::*;
+
+/// Run the storage migrations that the storage version found on chain calls for, as long as they
+/// fit in a single block, and start those that need to be spread across several blocks.
+pub(crate) fn migrate<T: Config>() -> Weight {
+    let mut weight = T::DbWeight::get().reads(1);
+
+    let on_chain_version = Pallet::<T>::on_chain_storage_version();
+    // There can be no more than `MaxOperators` operators, so counting them fits in a single block
+    if on_chain_version < 2 {
+        weight = weight.saturating_add(v2::migrate::<T>());
+    }
+    // Requests, subscriptions and feeds get migrated a few at a time from `on_initialize`, and the
+    // storage version is only bumped once all of them have been migrated
+    if on_chain_version < 4 && !OngoingMigration::<T>::exists() {
+        OngoingMigration::<T>::put(MigrationCursor::Requests(None));
+        weight = weight.saturating_add(T::DbWeight::get().reads_writes(1, 1));
+    }
+
+    weight
+}
+
+/// Migrate up to `MigrationEntriesPerBlock` storage entries, picking up right after the last ones
+/// that got migrated, and bump the storage version once there are no entries left.
+pub(crate) fn migrate_next_entries<T: Config>() -> Weight {
+    let db_weight = T::DbWeight::get();
+    let mut weight = db_weight.reads(1);
+
+    let mut cursor = match OngoingMigration::<T>::get() {
+        Some(cursor) => cursor,
+        None => return weight,
+    };
+    // The storage version stays at the one found on chain until the migration completes
+    let on_chain_version = Pallet::<T>::on_chain_storage_version();
+    weight = weight.saturating_add(db_weight.reads(1));
+
+    for _ in 0..T::MigrationEntriesPerBlock::get() {
+        // Finding the next key costs a read, even if there is none
+        weight = weight.saturating_add(db_weight.reads(1));
+        let next_cursor = match cursor {
+            MigrationCursor::Requests(last) => {
+                match next_key::<RequestId, RequestEntry<T>, Requests<T>>(last) {
+                    Some(request_id) => {
+                        if on_chain_version < 3 {
+                            weight = weight.saturating_add(v3::migrate_request::<T>(request_id));
+                        }
+                        weight = weight.saturating_add(v1::index_request::<T>(request_id));
+                        if on_chain_version < 4 {
+                            weight = weight.saturating_add(v4::restore_owner::<T>(request_id));
+                        }
+                        Some(MigrationCursor::Requests(Some(request_id)))
+                    }
+                    // Subscriptions and feeds only changed in version 3
+                    None if on_chain_version < 3 => Some(MigrationCursor::Subscriptions(None)),
+                    None => None,
+                }
+            }
+            MigrationCursor::Subscriptions(last) => {
+                match next_key::<SubscriptionId, Subscription<T>, Subscriptions<T>>(last) {
+                    Some(subscription_id) => {
+                        weight =
+                            weight.saturating_add(v3::migrate_subscription::<T>(subscription_id));
+                        Some(MigrationCursor::Subscriptions(Some(subscription_id)))
+                    }
+                    None => Some(MigrationCursor::Feeds(None)),
+                }
+            }
+            MigrationCursor::Feeds(last) => {
+                match next_key::<FeedId, FeedEntry<T>, Feeds<T>>(last) {
+                    Some(feed_id) => {
+                        weight = weight.saturating_add(v3::migrate_feed::<T>(feed_id));
+                        Some(MigrationCursor::Feeds(Some(feed_id)))
+                    }
+                    None => None,
+                }
+            }
+        };
+
+        cursor = match next_cursor {
+            Some(next_cursor) => next_cursor,
+            None => {
+                StorageVersion::new(4).put::<Pallet<T>>();
+                OngoingMigration::<T>::kill();
+
+                return weight.saturating_add(db_weight.writes(2));
+            }
+        };
+    }
+
+    OngoingMigration::<T>::put(cursor);
+
+    weight.saturating_add(db_weight.writes(1))
+}
+
+/// Get the key that comes after `last` in a storage map, or the first key if there is no `last`.
+/// Only the keys are decoded, so that this works even if the values cannot be decoded yet.
+fn next_key<K, V, M>(last: Option<K>) -> Option<K>
+where
+    K: FullCodec,
+    V: FullCodec,
+    M: IterableStorageMap<K, V> + StorageMapT<K, V>,
+{
+    match last {
+        Some(last) => M::iter_keys_from(M::hashed_key_for(last)).next(),
+        None => M::iter_keys().next(),
+    }
+}
+
+/// Version 1 indexes the requests that were already waiting for a result before there was an
+/// index of pending requests.
+pub mod v1 {
+    use super::*;
+
+    pub(crate) fn index_request<T: Config>(request_id: RequestId) -> Weight {
+        let db_weight = T::DbWeight::get();
+
+        if let Some((Some(_), None, _)) = Requests::<T>::get(request_id) {
+            if !PendingRequests::<T>::contains_key(request_id) {
+                PendingRequests::<T>::insert(request_id, ());
+                return db_weight.reads_writes(2, 2);
+            }
+        }
+
+        db_weight.reads(2)
+    }
+}
+
+/// Version 2 keeps count of the operators.
+pub mod v2 {
+    use super::*;
+
+    pub(crate) fn migrate<T: Config>() -> Weight {
+        let count = Operators::<T>::initialize_counter();
+
+        T::DbWeight::get().reads_writes(u64::from(count), 1)
+    }
+}
+
+/// Version 3 uses separate limits for the sizes of requests and results, which used to share the
+/// same limit. Whatever does not fit in its new limit gets dealt with as follows:
+/// - Requests that are still waiting for a result expire right away.
+/// - Results are replaced by their hash, as in hash-only mode.
+/// - Subscriptions come to an end.
+/// - Feeds are removed.
+pub mod v3 {
+    use super::*;
+
+    type OldQuery<T> = (Vec<u8>, BalanceFor<T>);
+    type OldResponse<T> = (TimestampFor<T>, [u8; 32], Vec<u8>);
+    type OldRequestEntry<T> = (
+        Option<OldQuery<T>>,
+        Option<OldResponse<T>>,
+        Option<<T as frame_system::Config>::AccountId>,
+    );
+
+    #[derive(Decode)]
+    struct OldSubscription<AccountId, Balance, BlockNumber> {
+        owner: AccountId,
+        bytes: Vec<u8>,
+        reward: Balance,
+        budget: Balance,
+        period: BlockNumber,
+    }
+
+    type OldSubscriptionFor<T> = OldSubscription<
+        <T as frame_system::Config>::AccountId,
+        BalanceFor<T>,
+        <T as frame_system::Config>::BlockNumber,
+    >;
+
+    pub(crate) fn migrate_request<T: Config>(request_id: RequestId) -> Weight {
+        let db_weight = T::DbWeight::get();
+        let mut weight = db_weight.reads(1);
+
+        let key = Requests::<T>::hashed_key_for(request_id);
+        let (query, response, requester) = match unhashed::get::<OldRequestEntry<T>>(&key) {
+            Some(old) => old,
+            None => return weight,
+        };
+        let pending = response.is_none();
+
+        let query = query.and_then(|(bytes, reward)| match bytes.try_into() {
+            Ok(bytes) => Some((bytes, reward)),
+            Err(()) => {
+                // Requests that no longer fit can never be resolved
+                if let (true, Some(requester)) = (pending, &requester) {
+                    traits::release_expired_request::<T>(request_id, requester, reward);
+                    weight = weight.saturating_add(db_weight.reads_writes(3, 9));
+                }
+                None
+            }
+        });
+        let response = response.map(|(timestamp, dr_tx_hash, bytes)| {
+            (timestamp, dr_tx_hash, bound_result::<T>(request_id, bytes))
+        });
+        Requests::<T>::insert(request_id, (query, response, requester));
+
+        weight.saturating_add(db_weight.writes(2))
+    }
+
+    pub(crate) fn migrate_subscription<T: Config>(subscription_id: SubscriptionId) -> Weight {
+        let db_weight = T::DbWeight::get();
+
+        let key = Subscriptions::<T>::hashed_key_for(subscription_id);
+        let old = match unhashed::get::<OldSubscriptionFor<T>>(&key) {
+            Some(old) => old,
+            None => return db_weight.reads(1),
+        };
+
+        match old.bytes.try_into() {
+            Ok(bytes) => Subscriptions::<T>::insert(
+                subscription_id,
+                Subscription {
+                    owner: old.owner,
+                    bytes,
+                    reward: old.reward,
+                    budget: old.budget,
+                    period: old.period,
+                },
+            ),
+            Err(()) => {
+                Subscriptions::<T>::remove(subscription_id);
+                T::Currency::unreserve(&old.owner, old.budget);
+                Pallet::<T>::deposit_event(Event::<T>::ExhaustedSubscription { subscription_id });
+            }
+        }
+
+        db_weight.reads_writes(2, 2)
+    }
+
+    pub(crate) fn migrate_feed<T: Config>(feed_id: FeedId) -> Weight {
+        let db_weight = T::DbWeight::get();
+
+        let key = Feeds::<T>::hashed_key_for(feed_id);
+        let (request_id, (timestamp, dr_tx_hash, bytes)) =
+            match unhashed::get::<(RequestId, OldResponse<T>)>(&key) {
+                Some(old) => old,
+                None => return db_weight.reads(1),
+            };
+
+        match bytes.try_into() {
+            Ok(bytes) => Feeds::<T>::insert(feed_id, (request_id, (timestamp, dr_tx_hash, bytes))),
+            Err(()) => Feeds::<T>::remove(feed_id),
+        }
+
+        db_weight.reads_writes(1, 1)
+    }
+
+    fn bound_result<T: Config>(
+        request_id: RequestId,
+        bytes: Vec<u8>,
+    ) -> BoundedVec<u8, T::MaxResultSize> {
+        if bytes.len() > T::MaxResultSize::get() as usize {
+            ResultHashes::<T>::insert(request_id, blake2_256(&bytes));
+            return Default::default();
+        }
+
+        bytes.try_into().unwrap_or_default()
+    }
+}
+
+/// Version 4 keeps the owners of requests once their results are reported, which used to be
+/// cleared. The owners of the results that were already reported are restored from the storage
+/// deposits of their requests. Results of requests posted before there were storage deposits are
+/// left without an owner, so they cannot be transferred.
+pub mod v4 {
+    use super::*;
+
+    pub(crate) fn restore_owner<T: Config>(request_id: RequestId) -> Weight {
+        let db_weight = T::DbWeight::get();
+
+        if let Some((query, Some(response), None)) = Requests::<T>::get(request_id) {
+            if let Some((depositor, _)) = Deposits::<T>::get(request_id) {
+                Requests::<T>::insert(request_id, (query, Some(response), Some(depositor)));
+                return db_weight.reads_writes(2, 1);
+            }
+
+            return db_weight.reads(2);
+        }
+
+        db_weight.reads(1)
+    }
+}
@@ -1,9 +1,16 @@
+use std::cell::RefCell;
+
 use frame_support::{
     parameter_types,
-    traits::{ConstU32, ConstU64, GenesisBuild},
+    traits::{ConstU32, ConstU64, EnsureOrigin, GenesisBuild},
+};
+#[cfg(feature = "ocw")]
+use sp_runtime::testing::TestSignature;
+use sp_runtime::testing::{Header, UintAuthorityId};
+use sp_runtime::{traits::IdentityLookup, Percent};
+use xcm::latest::{
+    Junction::Parachain, Junctions::X1, MultiAsset, MultiLocation, SendResult, SendXcm, Xcm,
 };
-use sp_runtime::testing::Header;
-use sp_runtime::traits::IdentityLookup;
 
 use sp_core::H256;
 
@@ -79,17 +86,194 @@ impl pallet_timestamp::Config for Test {
     type WeightInfo = ();
 }
 
-pub const MAX_WITNET_BYTE_SIZE: u16 = 2048;
+/// Accounts from this one onwards are treated as the sovereign accounts of sibling parachains.
+pub const SIBLING_ACCOUNT_OFFSET: u64 = 1_000;
+
+/// The parachain ID of the sibling that posts requests in the tests.
+pub const SIBLING_PARA_ID: u32 = 2_000;
+
+pub const XCM_CALLBACK_INDEX: [u8; 2] = [42, 0];
+pub const XCM_FEE: u64 = 23;
+pub const XCM_FEE_COLLECTOR: u64 = 999;
+pub const XCM_FEE_ASSET_AMOUNT: u128 = 1_000_000;
+
+thread_local! {
+    pub static SENT_XCM: RefCell<Vec<(MultiLocation, Xcm<()>)>> = RefCell::new(Vec::new());
+}
+
+/// An origin that treats signed origins from the sovereign accounts of siblings as XCM origins.
+pub struct EnsureSibling;
+
+impl EnsureOrigin<Origin> for EnsureSibling {
+    type Success = MultiLocation;
+
+    fn try_origin(o: Origin) -> Result<Self::Success, Origin> {
+        Into::<Result<frame_system::RawOrigin<u64>, Origin>>::into(o).and_then(|o| match o {
+            frame_system::RawOrigin::Signed(who) if who >= SIBLING_ACCOUNT_OFFSET => {
+                let para_id = (who - SIBLING_ACCOUNT_OFFSET) as u32;
+                Ok(MultiLocation::new(1, X1(Parachain(para_id))))
+            }
+            o => Err(Origin::from(o)),
+        })
+    }
+
+    #[cfg(feature = "runtime-benchmarks")]
+    fn successful_origin() -> Origin {
+        Origin::signed(SIBLING_ACCOUNT_OFFSET + u64::from(SIBLING_PARA_ID))
+    }
+}
+
+pub struct SiblingToAccountId;
+
+impl xcm_executor::traits::Convert<MultiLocation, u64> for SiblingToAccountId {
+    fn convert(location: MultiLocation) -> Result<u64, MultiLocation> {
+        match location {
+            MultiLocation {
+                parents: 1,
+                interior: X1(Parachain(para_id)),
+            } => Ok(SIBLING_ACCOUNT_OFFSET + u64::from(para_id)),
+            location => Err(location),
+        }
+    }
+}
+
+/// Only the sibling that posts requests in the tests has a callback for receiving results.
+pub struct SiblingCallbackIndex;
+
+impl sp_runtime::traits::Convert<MultiLocation, Option<[u8; 2]>> for SiblingCallbackIndex {
+    fn convert(location: MultiLocation) -> Option<[u8; 2]> {
+        match location {
+            MultiLocation {
+                parents: 1,
+                interior: X1(Parachain(SIBLING_PARA_ID)),
+            } => Some(XCM_CALLBACK_INDEX),
+            _ => None,
+        }
+    }
+}
+
+/// An XCM sender that keeps track of all the messages that have been sent.
+pub struct TestXcmSender;
+
+impl SendXcm for TestXcmSender {
+    fn send_xcm(destination: impl Into<MultiLocation>, message: Xcm<()>) -> SendResult {
+        SENT_XCM.with(|sent| sent.borrow_mut().push((destination.into(), message)));
+
+        Ok(())
+    }
+}
+
+pub fn sent_xcm() -> Vec<(MultiLocation, Xcm<()>)> {
+    SENT_XCM.with(|sent| sent.borrow().clone())
+}
+
+pub const MAX_REQUEST_SIZE: u16 = 2048;
+pub const MAX_RESULT_SIZE: u16 = 1024;
+pub const MAX_RESULT_AGE: u64 = 60_000;
+pub const MAX_OPEN_REQUESTS: u32 = 8;
+pub const INACTIVITY_PERIOD: u64 = 200;
+pub const MAX_OPERATORS: u32 = 3;
+pub const UNSIGNED_PRIORITY: u64 = 1 << 20;
+pub const RANDOMNESS_DELAY: u64 = 2;
 
 parameter_types! {
-    pub const MaxWitnetByteSize: u16 = MAX_WITNET_BYTE_SIZE;
+    pub const MaxRequestSize: u16 = MAX_REQUEST_SIZE;
+    pub const MaxResultSize: u16 = MAX_RESULT_SIZE;
+    pub const XcmFeeCollector: u64 = XCM_FEE_COLLECTOR;
+    pub XcmFeeAsset: MultiAsset = (MultiLocation::parent(), XCM_FEE_ASSET_AMOUNT).into();
+    // Assignments are disabled unless a test enables them
+    pub static ExclusivityWindow: u64 = 0;
+    pub static MinOperators: u32 = 1;
+    pub static HashOnlyResults: bool = false;
+    pub ErrorRefund: Percent = Percent::from_percent(80);
 }
 
 impl pallet_witnet_oracle::Config for Test {
     type Event = Event;
     type Currency = Balances;
-    type MaxByteSize = MaxWitnetByteSize;
+    type MaxRequestSize = MaxRequestSize;
+    type MaxResultSize = MaxResultSize;
+    type RequestDepositBase = ConstU64<0>;
+    type RequestDepositPerByte = ConstU64<1>;
+    type MaxOpenRequests = ConstU32<MAX_OPEN_REQUESTS>;
+    type MaxOperators = ConstU32<MAX_OPERATORS>;
+    type MinOperators = MinOperators;
+    type MaxOperatorInfoLength = ConstU32<32>;
+    type ErrorRefund = ErrorRefund;
+    type HashOnlyResults = HashOnlyResults;
     type TimeProvider = pallet_timestamp::Pallet<Test>;
+    type MaxResultAge = ConstU64<MAX_RESULT_AGE>;
+    type DefaultRequestTimeout = ConstU64<100>;
+    type MaxExpiriesPerBlock = ConstU32<2>;
+    type XcmOrigin = EnsureSibling;
+    type LocationToAccountId = SiblingToAccountId;
+    type XcmSender = TestXcmSender;
+    type XcmCallbackIndex = SiblingCallbackIndex;
+    type XcmFee = ConstU64<XCM_FEE>;
+    type XcmFeeCollector = XcmFeeCollector;
+    type XcmFeeAsset = XcmFeeAsset;
+    type XcmCallbackWeight = ConstU64<1_000_000_000>;
+    type MaxSubscriptionsPerBlock = ConstU32<2>;
+    type PauseOrigin = frame_system::EnsureRoot<u64>;
+    type DisputeOrigin = frame_system::EnsureRoot<u64>;
+    type InactivityPeriod = ConstU64<INACTIVITY_PERIOD>;
+    type OnInactiveOperator = pallet_witnet_oracle::traits::RemoveInactiveOperators<Test>;
+    type ExclusivityWindow = ExclusivityWindow;
+    #[cfg(feature = "ocw")]
+    type OffchainRelayer = pallet_witnet_oracle::ocw::WitnetNodeRelayer<Test, TestOperatorId>;
+    #[cfg(not(feature = "ocw"))]
+    type OffchainRelayer = ();
+    type OperatorKey = UintAuthorityId;
+    type UnsignedPriority = ConstU64<UNSIGNED_PRIORITY>;
+    type UnsignedLongevity = ConstU64<5>;
+    type MigrationEntriesPerBlock = ConstU32<2>;
+    type RandomnessDelay = ConstU64<RANDOMNESS_DELAY>;
+    type WeightInfo = ();
+}
+
+/// The extrinsics that the off-chain worker submits to the transaction pool.
+#[cfg(feature = "ocw")]
+pub type Extrinsic = sp_runtime::testing::TestXt<Call, ()>;
+
+/// Operator keys that identify the accounts of the tests, which are `u64` numbers.
+#[cfg(feature = "ocw")]
+pub struct TestOperatorId;
+
+#[cfg(feature = "ocw")]
+impl frame_system::offchain::AppCrypto<UintAuthorityId, TestSignature> for TestOperatorId {
+    type RuntimeAppPublic = UintAuthorityId;
+    type GenericPublic = UintAuthorityId;
+    type GenericSignature = TestSignature;
+}
+
+#[cfg(feature = "ocw")]
+impl frame_system::offchain::SigningTypes for Test {
+    type Public = UintAuthorityId;
+    type Signature = TestSignature;
+}
+
+#[cfg(feature = "ocw")]
+impl<LocalCall> frame_system::offchain::SendTransactionTypes<LocalCall> for Test
+where
+    Call: From<LocalCall>,
+{
+    type OverarchingCall = Call;
+    type Extrinsic = Extrinsic;
+}
+
+#[cfg(feature = "ocw")]
+impl<LocalCall> frame_system::offchain::CreateSignedTransaction<LocalCall> for Test
+where
+    Call: From<LocalCall>,
+{
+    fn create_transaction<C: frame_system::offchain::AppCrypto<Self::Public, Self::Signature>>(
+        call: Call,
+        _public: UintAuthorityId,
+        _account: u64,
+        nonce: u64,
+    ) -> Option<(Call, (u64, ()))> {
+        Some((call, (nonce, ())))
+    }
 }
 
 #[derive(Default)]
@@ -99,8 +283,12 @@ impl ExtBuilder {
     pub fn build(self) -> sp_io::TestExternalities {
         // Account #5 will be pre-approved as an operator
         let operators = vec![5];
-        // Fund all accounts in [0, 10) with a balance of 1_000
-        let balances = (0..10).map(|i| (i, 1_000)).collect::<Vec<_>>();
+        // Fund all accounts in [0, 10) with a balance of 1_000, and so the sibling parachain
+        let sibling_account = SIBLING_ACCOUNT_OFFSET + u64::from(SIBLING_PARA_ID);
+        let balances = (0..10)
+            .chain(Some(sibling_account))
+            .map(|i| (i, 1_000))
+            .collect::<Vec<_>>();
 
         let mut t = frame_system::GenesisConfig::default()
             .build_storage::<Test>()
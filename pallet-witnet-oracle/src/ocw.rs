@@ -0,0 +1,383 @@
+//! A relayer that operators can run from the off-chain workers of their nodes.
+//!
+//! On every block, the worker goes through a page of the pending requests. It sends every request
+//! that it has not relayed yet to a Witnet node through JSON-RPC, and keeps the hash of the
+//! resulting data request transaction in the off-chain storage. Once the node knows the tally of
+//! that data request, the worker reports it, either in a signed transaction with
+//! `WitnetNodeRelayer`, or in an unsigned one that carries a payload signed with the operator key
+//! with `UnsignedWitnetNodeRelayer`. Both look for keys of type `crypto::KEY_TYPE` in the
+//! keystore.
+//!
+//! The URL of the JSON-RPC endpoint is read as raw bytes from the persistent off-chain storage at
+//! `NODE_URL_KEY`, so that it can be set with `offchain_localStorageSet`, and defaults to
+//! `DEFAULT_NODE_URL`. The fee of the data request transactions is read the same way from
+//! `FEE_KEY`, as a decimal number of nanowits, and defaults to `DEFAULT_FEE`.
+//!
+//! Requests go to the node through `sendRequest`, converted into JSON with
+//! `witnet::send_request_params`. Their tallies are looked up through `dataRequestReport`, and
+//! the block that includes each tally through `getBlock`. Results are only reported once that block
+//! has been confirmed by a superblock, and they are timestamped with the time of its checkpoint.
+//!
+//! Requests that are exclusively assigned to a different operator are left alone until their
+//! exclusivity window is over, as their results could not be reported anyway.
+//!
+//! Off-chain workers of consecutive blocks may run at the same time, so the whole relaying is
+//! guarded by a lock in the off-chain storage, which keeps them from relaying the same requests
+//! twice.
+
+use frame_support::{
+    sp_io::offchain::{local_storage_get, timestamp},
+    sp_runtime::{
+        offchain::{
+            http,
+            storage::StorageValueRef,
+            storage_lock::{StorageLock, Time},
+            Duration, StorageKind,
+        },
+        traits::IdentifyAccount,
+        RuntimeAppPublic,
+    },
+    sp_std::{str, vec, vec::Vec},
+};
+use frame_system::offchain::{
+    AppCrypto, CreateSignedTransaction, SendSignedTransaction, SendTransactionTypes, Signer,
+    SubmitTransaction,
+};
+use lite_json::{parse_json, JsonValue};
+
+use crate::prelude::*;
+
+use super::*;
+
+/// The key of the URL of the Witnet node in the persistent off-chain storage.
+pub const NODE_URL_KEY: &[u8] = b"witnet-oracle::node-url";
+
+/// The default URL of the Witnet node, i.e. the HTTP endpoint of its JSON-RPC server listening on
+/// the local host, as opposed to the TCP one, which listens on port 21338.
+pub const DEFAULT_NODE_URL: &str = "http://127.0.0.1:21339";
+
+/// The key of the fee of data request transactions in the persistent off-chain storage.
+pub const FEE_KEY: &[u8] = b"witnet-oracle::fee";
+
+/// The default fee of data request transactions, in nanowits.
+pub const DEFAULT_FEE: u64 = 1;
+
+/// How many pending requests to go through on every block.
+const MAX_REQUESTS_PER_RUN: u32 = 10;
+
+/// How long to wait for the Witnet node to answer a call.
+const HTTP_TIMEOUT_MILLIS: u64 = 3_000;
+
+/// How long the lock lasts at most, which covers waiting for the node to answer every call of a
+/// run.
+const LOCK_DEADLINE_MILLIS: u64 = 3 * MAX_REQUESTS_PER_RUN as u64 * HTTP_TIMEOUT_MILLIS;
+
+const LOCK_KEY: &[u8] = b"witnet-oracle::lock";
+const CURSOR_KEY: &[u8] = b"witnet-oracle::cursor";
+const DR_TX_HASH_PREFIX: &[u8] = b"witnet-oracle::dr-tx-hash::";
+
+/// Relay pending requests to the Witnet node, and report their results through signed
+/// transactions, with the first key of type `C`.
+pub struct WitnetNodeRelayer<T, C>(PhantomData<(T, C)>);
+
+impl<T, C> OffchainRelayer<T> for WitnetNodeRelayer<T, C>
+where
+    T: Config + CreateSignedTransaction<Call<T>>,
+    C: AppCrypto<T::Public, T::Signature>,
+{
+    fn relay(_block_number: T::BlockNumber) {
+        // Ensure that there is an account key for signing transactions, and always sign with the
+        // same one, so that it is known which requests are assigned to the reporter
+        let public: T::Public = match C::RuntimeAppPublic::all().into_iter().next() {
+            Some(key) => C::GenericPublic::from(key).into(),
+            None => return,
+        };
+        let reporter = public.clone().into_account();
+        let signer = Signer::<T, C>::any_account().with_filter(vec![public]);
+        if !signer.can_sign() {
+            return;
+        }
+
+        relay_pending_requests::<T>(
+            &reporter,
+            |request_id, timestamp, dr_tx_hash, result_bytes, status| {
+                let sent = signer.send_signed_transaction(|_| Call::report_result {
+                    request_id,
+                    timestamp,
+                    dr_tx_hash,
+                    result_bytes: result_bytes.clone(),
+                    status,
+                });
+                match sent {
+                    Some((_, Ok(()))) => Ok(()),
+                    _ => Err(()),
+                }
+            },
+        );
+    }
+}
+
+/// Relay pending requests to the Witnet node, and report their results through unsigned
+/// transactions, on behalf of the first operator whose operator key is in the keystore. This
+/// needs no funded account, as unsigned transactions pay no fees.
+pub struct UnsignedWitnetNodeRelayer<T>(PhantomData<T>);
+
+impl<T> OffchainRelayer<T> for UnsignedWitnetNodeRelayer<T>
+where
+    T: Config + SendTransactionTypes<Call<T>>,
+{
+    fn relay(_block_number: T::BlockNumber) {
+        // Ensure that there is an operator key for signing results
+        let local_keys = T::OperatorKey::all();
+        let (operator, key) =
+            match OperatorKeys::<T>::iter().find(|(_, key)| local_keys.contains(key)) {
+                Some(operator_key) => operator_key,
+                None => return,
+            };
+
+        relay_pending_requests::<T>(
+            &operator,
+            |request_id, timestamp, dr_tx_hash, result_bytes, status| {
+                let payload = ResultPayload {
+                    operator: operator.clone(),
+                    request_id,
+                    timestamp,
+                    dr_tx_hash,
+                    result_bytes,
+                    status,
+                };
+                let signature = key.sign(&payload.signing_bytes()).ok_or(())?;
+
+                SubmitTransaction::<T, Call<T>>::submit_unsigned_transaction(
+                    Call::report_result_unsigned { payload, signature }.into(),
+                )
+            },
+        );
+    }
+}
+
+/// Move a page of the pending requests one step forward, i.e. send them to the Witnet node or
+/// pass their results to `report`, which reports them on behalf of `reporter`.
+fn relay_pending_requests<T: Config>(
+    reporter: &T::AccountId,
+    report: impl Fn(RequestId, TimestampFor<T>, [u8; 32], Vec<u8>, ResultStatus) -> Result<(), ()>,
+) {
+    // Leave it all to the worker that is already running, if any
+    let mut lock =
+        StorageLock::<Time>::with_deadline(LOCK_KEY, Duration::from_millis(LOCK_DEADLINE_MILLIS));
+    let _guard = match lock.try_lock() {
+        Ok(guard) => guard,
+        Err(_) => return,
+    };
+
+    let node_url = local_storage_get(StorageKind::PERSISTENT, NODE_URL_KEY)
+        .unwrap_or_else(|| DEFAULT_NODE_URL.as_bytes().to_vec());
+    let node_url = match str::from_utf8(&node_url) {
+        Ok(node_url) => node_url,
+        Err(_) => return,
+    };
+    let fee = match local_storage_get(StorageKind::PERSISTENT, FEE_KEY) {
+        Some(fee) => match str::from_utf8(&fee).ok().and_then(|fee| fee.parse().ok()) {
+            Some(fee) => fee,
+            None => return,
+        },
+        None => DEFAULT_FEE,
+    };
+
+    // Go through the pending requests page by page, starting over after the last page
+    let mut cursor = StorageValueRef::persistent(CURSOR_KEY);
+    let start_after = cursor.get::<RequestId>().ok().flatten();
+    let pending = Pallet::<T>::pending_requests(start_after, MAX_REQUESTS_PER_RUN);
+    match pending.last() {
+        Some(last) if pending.len() == MAX_REQUESTS_PER_RUN as usize => cursor.set(last),
+        _ => cursor.clear(),
+    }
+
+    for request_id in pending {
+        // Leave requests assigned to someone else for later, once anyone can report their results
+        let assigned = Pallet::<T>::exclusive_operator(request_id);
+        if matches!(assigned, Some(operator) if operator != *reporter) {
+            continue;
+        }
+
+        // Failing to relay a request must not get in the way of relaying the others
+        let _ = relay_request::<T>(node_url, fee, request_id, &report);
+    }
+}
+
+fn relay_request<T: Config>(
+    node_url: &str,
+    fee: u64,
+    request_id: RequestId,
+    report: impl Fn(RequestId, TimestampFor<T>, [u8; 32], Vec<u8>, ResultStatus) -> Result<(), ()>,
+) -> Result<(), ()> {
+    let key = [DR_TX_HASH_PREFIX, &request_id.encode()[..]].concat();
+    let mut stored_hash = StorageValueRef::persistent(&key);
+
+    // Send the request to Witnet if it has not been sent yet, and look for its tally later on
+    let dr_tx_hash = match stored_hash.get::<[u8; 32]>().map_err(|_| ())? {
+        Some(dr_tx_hash) => dr_tx_hash,
+        None => {
+            let bytes = Pallet::<T>::request_bytes(request_id).ok_or(())?;
+            let dr_tx_hash = send_request(node_url, &bytes, fee)?;
+            stored_hash.set(&dr_tx_hash);
+
+            return Ok(());
+        }
+    };
+
+    let (result_bytes, tally_block_hash) = match data_request_tally(node_url, &dr_tx_hash)? {
+        Some(tally) => tally,
+        None => return Ok(()),
+    };
+
+    // Results are final once the block that includes the tally gets confirmed by a superblock,
+    // and they get timestamped with the checkpoint of that block
+    let checkpoint = match confirmed_block_checkpoint(node_url, &tally_block_hash)? {
+        Some(checkpoint) => checkpoint,
+        None => return Ok(()),
+    };
+    let timestamp = TimestampFor::<T>::try_from(witnet::checkpoint_timestamp_millis(checkpoint))
+        .map_err(|_| ())?;
+    let status = witnet::result_status(&result_bytes);
+    report(request_id, timestamp, dr_tx_hash, result_bytes, status)?;
+    stored_hash.clear();
+
+    Ok(())
+}
+
+/// Send a request to the Witnet node, and get the hash of its data request transaction.
+fn send_request(node_url: &str, bytes: &[u8], fee: u64) -> Result<[u8; 32], ()> {
+    let params = witnet::send_request_params(bytes, fee)?;
+
+    match json_rpc(node_url, b"sendRequest", &params)? {
+        JsonValue::String(dr_tx_hash) => hex_decode_hash(&dr_tx_hash),
+        _ => Err(()),
+    }
+}
+
+/// Get the tally of a data request from the Witnet node, along with the hash of the block that
+/// includes it, if the data request has already been resolved.
+fn data_request_tally(
+    node_url: &str,
+    dr_tx_hash: &[u8; 32],
+) -> Result<Option<(Vec<u8>, [u8; 32])>, ()> {
+    let params = [&b"[\""[..], &hex_encode(dr_tx_hash)[..], &b"\"]"[..]].concat();
+    let report = json_rpc(node_url, b"dataRequestReport", &params)?;
+
+    let tally = match field(&report, "tally") {
+        Some(JsonValue::Null) | None => return Ok(None),
+        Some(tally) => tally,
+    };
+    let block_hash = match field(&report, "block_hash_tally_tx") {
+        Some(JsonValue::String(block_hash)) => hex_decode_hash(block_hash)?,
+        _ => return Err(()),
+    };
+
+    match field(tally, "tally") {
+        Some(JsonValue::Array(bytes)) => bytes
+            .iter()
+            .map(|byte| u8::try_from(integer(byte)?).map_err(|_| ()))
+            .collect::<Result<Vec<u8>, ()>>()
+            .map(|bytes| Some((bytes, block_hash))),
+        _ => Err(()),
+    }
+}
+
+/// Get the checkpoint of a block from the Witnet node, if the block has already been confirmed by
+/// a superblock.
+fn confirmed_block_checkpoint(node_url: &str, block_hash: &[u8; 32]) -> Result<Option<u64>, ()> {
+    let params = [&b"[\""[..], &hex_encode(block_hash)[..], &b"\"]"[..]].concat();
+    let block = json_rpc(node_url, b"getBlock", &params)?;
+
+    match field(&block, "confirmed") {
+        Some(JsonValue::Boolean(true)) => {}
+        Some(JsonValue::Boolean(false)) | None => return Ok(None),
+        Some(_) => return Err(()),
+    }
+
+    let checkpoint = field(&block, "block_header")
+        .and_then(|header| field(header, "beacon"))
+        .and_then(|beacon| field(beacon, "checkpoint"))
+        .ok_or(())?;
+
+    integer(checkpoint).map(Some)
+}
+
+fn integer(json: &JsonValue) -> Result<u64, ()> {
+    match json {
+        JsonValue::Number(number) if !number.negative && number.fraction_length == 0 => {
+            Ok(number.integer)
+        }
+        _ => Err(()),
+    }
+}
+
+fn json_rpc(node_url: &str, method: &[u8], params: &[u8]) -> Result<JsonValue, ()> {
+    let body = [
+        &b"{\"jsonrpc\":\"2.0\",\"id\":1,\"method\":\""[..],
+        method,
+        b"\",\"params\":",
+        params,
+        b"}",
+    ]
+    .concat();
+
+    let deadline = timestamp().add(Duration::from_millis(HTTP_TIMEOUT_MILLIS));
+    let response = http::Request::post(node_url, vec![body])
+        .add_header("Content-Type", "application/json")
+        .deadline(deadline)
+        .send()
+        .map_err(|_| ())?
+        .try_wait(deadline)
+        .map_err(|_| ())?
+        .map_err(|_| ())?;
+    if response.code != 200 {
+        return Err(());
+    }
+
+    let body = response.body().collect::<Vec<u8>>();
+    let body = str::from_utf8(&body).map_err(|_| ())?;
+    let response = parse_json(body).map_err(|_| ())?;
+
+    field(&response, "result").cloned().ok_or(())
+}
+
+fn field<'a>(json: &'a JsonValue, name: &str) -> Option<&'a JsonValue> {
+    match json {
+        JsonValue::Object(fields) => fields
+            .iter()
+            .find(|(key, _)| key.iter().copied().eq(name.chars()))
+            .map(|(_, value)| value),
+        _ => None,
+    }
+}
+
+fn hex_encode(bytes: &[u8]) -> Vec<u8> {
+    const DIGITS: &[u8; 16] = b"0123456789abcdef";
+
+    bytes
+        .iter()
+        .flat_map(|byte| {
+            [
+                DIGITS[usize::from(byte >> 4)],
+                DIGITS[usize::from(byte & 0x0f)],
+            ]
+        })
+        .collect()
+}
+
+fn hex_decode_hash(digits: &[char]) -> Result<[u8; 32], ()> {
+    if digits.len() != 64 {
+        return Err(());
+    }
+
+    let mut hash = [0; 32];
+    for (byte, pair) in hash.iter_mut().zip(digits.chunks(2)) {
+        let high = pair[0].to_digit(16).ok_or(())?;
+        let low = pair[1].to_digit(16).ok_or(())?;
+        *byte = (high << 4 | low) as u8;
+    }
+
+    Ok(hash)
+}
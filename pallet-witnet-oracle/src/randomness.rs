@@ -0,0 +1,21 @@
+//! Witnet randomness requests, and parsing of their results.
+//!
+//! The bytes of the request are the same `DataRequestOutput` that the Witnet randomness contracts
+//! on EVM chains use: a single RNG source, whose values are concatenated and hashed by the tally,
+//! so that no single witness can bias the outcome. The result is thus a CBOR byte string of 32
+//! bytes.
+
+/// The protobuf encoded Witnet `DataRequestOutput` of a randomness request.
+pub const RANDOMNESS_REQUEST: [u8; 32] = [
+    0x0a, 0x0f, 0x12, 0x05, 0x08, 0x02, 0x1a, 0x01, 0x80, 0x1a, 0x02, 0x10, 0x02, 0x22, 0x02, 0x10,
+    0x0b, 0x10, 0xe8, 0x07, 0x18, 0x0a, 0x20, 0x0a, 0x28, 0x33, 0x30, 0x80, 0x94, 0xeb, 0xdc, 0x03,
+];
+
+/// Get the random bytes out of the CBOR encoded result of a randomness request, if the result is
+/// a byte string of the expected length.
+pub fn parse_randomness(result_bytes: &[u8]) -> Option<[u8; 32]> {
+    match result_bytes {
+        [0x58, 0x20, randomness @ ..] => randomness.try_into().ok(),
+        _ => None,
+    }
+}
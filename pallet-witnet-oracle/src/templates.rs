@@ -0,0 +1,161 @@
+//! Filling of the placeholders of request templates.
+//!
+//! The bytes of requests are protobuf encoded Witnet `DataRequestOutput` messages. Placeholders
+//! go from `\0\` to `\9\`, and they are only filled inside the text fields of the data sources:
+//! URLs, bodies and headers. As filling a placeholder changes the length of the fields that
+//! contain it, all the enclosing messages get encoded again.
+
+use frame_support::sp_std::{convert::TryFrom, vec::Vec};
+use pallet_witnet_oracle_primitives::protobuf::{read_varint, take};
+
+/// The maximum number of arguments, as placeholders are made of a single digit.
+pub const MAX_TEMPLATE_ARGS: u32 = 10;
+
+/// The reasons why filling a template may fail.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum FillError {
+    /// The template is not a valid protobuf message, or the arguments do not match its
+    /// placeholders.
+    Invalid,
+    /// The filled template would be bigger than the maximum size.
+    Oversized,
+}
+
+impl From<()> for FillError {
+    fn from(_: ()) -> Self {
+        FillError::Invalid
+    }
+}
+
+/// The messages whose fields may contain placeholders, be it directly or through other messages.
+#[derive(Clone, Copy)]
+enum Message {
+    DataRequestOutput,
+    RadRequest,
+    RadRetrieve,
+    StringPair,
+}
+
+/// The kinds of length delimited fields that can be found in a message.
+enum Field {
+    Message(Message),
+    Text,
+    Opaque,
+}
+
+impl Message {
+    fn field(self, number: u64) -> Field {
+        match (self, number) {
+            // `DataRequestOutput.data_request`
+            (Message::DataRequestOutput, 1) => Field::Message(Message::RadRequest),
+            // `RADRequest.retrieve`
+            (Message::RadRequest, 2) => Field::Message(Message::RadRetrieve),
+            // `RADRetrieve.url` and `RADRetrieve.body`
+            (Message::RadRetrieve, 2) | (Message::RadRetrieve, 4) => Field::Text,
+            // `RADRetrieve.headers`
+            (Message::RadRetrieve, 5) => Field::Message(Message::StringPair),
+            // `StringPair.left` and `StringPair.right`
+            (Message::StringPair, _) => Field::Text,
+            _ => Field::Opaque,
+        }
+    }
+}
+
+/// Fill the placeholders of a template with the given arguments.
+///
+/// Fails if there are too many arguments, if the template is not a valid protobuf message, or if
+/// any of its placeholders has no matching argument. Filling stops as soon as the output grows
+/// beyond `max_size`, so that arguments repeated across many placeholders cannot take up an
+/// unbounded amount of memory.
+pub(crate) fn fill_template(
+    template: &[u8],
+    args: &[&[u8]],
+    max_size: usize,
+) -> Result<Vec<u8>, FillError> {
+    if args.len() > MAX_TEMPLATE_ARGS as usize {
+        return Err(FillError::Invalid);
+    }
+
+    fill_message(Message::DataRequestOutput, template, args, max_size)
+}
+
+fn fill_message(
+    message: Message,
+    mut input: &[u8],
+    args: &[&[u8]],
+    max_size: usize,
+) -> Result<Vec<u8>, FillError> {
+    let mut output = Vec::with_capacity(input.len());
+
+    while !input.is_empty() {
+        let key = read_varint(&mut input)?;
+        write_varint(&mut output, key);
+
+        match key & 0x07 {
+            // Varint
+            0 => {
+                let value = read_varint(&mut input)?;
+                write_varint(&mut output, value);
+            }
+            // 64 bits
+            1 => output.extend_from_slice(take(&mut input, 8)?),
+            // Length delimited
+            2 => {
+                let len = usize::try_from(read_varint(&mut input)?).map_err(|_| ())?;
+                let payload = take(&mut input, len)?;
+                let payload = match message.field(key >> 3) {
+                    Field::Message(inner) => fill_message(inner, payload, args, max_size)?,
+                    Field::Text => fill_text(payload, args, max_size)?,
+                    Field::Opaque => payload.to_vec(),
+                };
+                write_varint(&mut output, payload.len() as u64);
+                output.extend(payload);
+            }
+            // 32 bits
+            5 => output.extend_from_slice(take(&mut input, 4)?),
+            // Groups are deprecated, and Witnet does not use them
+            _ => return Err(FillError::Invalid),
+        }
+
+        if output.len() > max_size {
+            return Err(FillError::Oversized);
+        }
+    }
+
+    Ok(output)
+}
+
+fn fill_text(text: &[u8], args: &[&[u8]], max_size: usize) -> Result<Vec<u8>, FillError> {
+    let mut output = Vec::with_capacity(text.len());
+
+    let mut i = 0;
+    while i < text.len() {
+        match text.get(i..i + 3) {
+            Some(&[b'\\', digit @ b'0'..=b'9', b'\\']) => {
+                let arg = args
+                    .get(usize::from(digit - b'0'))
+                    .ok_or(FillError::Invalid)?;
+                output.extend_from_slice(arg);
+                i += 3;
+            }
+            _ => {
+                output.push(text[i]);
+                i += 1;
+            }
+        }
+
+        if output.len() > max_size {
+            return Err(FillError::Oversized);
+        }
+    }
+
+    Ok(output)
+}
+
+fn write_varint(output: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        output.push((value as u8 & 0x7f) | 0x80);
+        value >>= 7;
+    }
+    output.push(value as u8);
+}
@@ -1,14 +1,60 @@
-use frame_support::{assert_ok, dispatch::DispatchResult};
-use sp_runtime::traits::Zero;
+use codec::Encode;
+use frame_support::{
+    assert_ok,
+    dispatch::{
+        DispatchErrorWithPostInfo, DispatchResult, DispatchResultWithPostInfo, GetDispatchInfo,
+        PostDispatchInfo,
+    },
+    traits::{GetStorageVersion, Hooks, Randomness, StorageVersion},
+    weights::{Pays, Weight},
+    BoundedVec, StorageHasher, Twox64Concat,
+};
+use sp_runtime::{
+    testing::UintAuthorityId,
+    traits::{BlakeTwo256, Hash, ValidateUnsigned, Zero},
+    transaction_validity::{InvalidTransaction, TransactionSource, ValidTransaction},
+    DispatchError, RuntimeAppPublic,
+};
+use std::convert::TryInto;
+use xcm::latest::{
+    Instruction::{BuyExecution, Transact, WithdrawAsset},
+    Junction::Parachain,
+    Junctions::X1,
+    MultiAsset, MultiLocation, OriginKind,
+    WeightLimit::Unlimited,
+    Xcm,
+};
 
 use crate::{
-    mock::{ExtBuilder, Origin, System, Test, Witnet, MAX_WITNET_BYTE_SIZE},
+    mock::{
+        sent_xcm, Event, ExclusivityWindow, ExtBuilder, HashOnlyResults, MinOperators, Origin,
+        System, Test, Witnet, INACTIVITY_PERIOD, MAX_OPEN_REQUESTS, MAX_OPERATORS,
+        MAX_REQUEST_SIZE, MAX_RESULT_AGE, MAX_RESULT_SIZE, RANDOMNESS_DELAY,
+        SIBLING_ACCOUNT_OFFSET, SIBLING_PARA_ID, UNSIGNED_PRIORITY, XCM_CALLBACK_INDEX, XCM_FEE,
+        XCM_FEE_ASSET_AMOUNT, XCM_FEE_COLLECTOR,
+    },
     prelude::*,
+    randomness,
 };
 
 fn post_dummy_request(origin: Origin, reward: Option<BalanceFor<Test>>) -> DispatchResult {
     let reward = reward.unwrap_or_default();
     Witnet::post_request(origin, reward, vec![])
+        .map(|_| ())
+        .map_err(|e| e.error)
+}
+
+fn report_result(
+    origin: Origin,
+    request_id: RequestId,
+    timestamp: TimestampFor<Test>,
+    dr_tx_hash: [u8; 32],
+    result_bytes: Vec<u8>,
+    status: ResultStatus,
+) -> DispatchResult {
+    Witnet::report_result(origin, request_id, timestamp, dr_tx_hash, result_bytes, status)
+        .map(|_| ())
+        .map_err(|e| e.error)
 }
 
 #[test]
@@ -17,7 +63,7 @@ fn test_post_request() {
         let reward = 123;
         let requester_id = 7;
         let requester = Origin::signed(7);
-        let max_byte_size = usize::from(MAX_WITNET_BYTE_SIZE);
+        let max_byte_size = usize::from(MAX_REQUEST_SIZE);
 
         let initial_requester_free_balance =
             <Test as WitnetConfig>::Currency::free_balance(&requester_id);
@@ -31,7 +77,7 @@ fn test_post_request() {
             vec![0; max_byte_size + 1],
         );
         let expected = Err(WitnetError::<Test>::OversizedRequest.into());
-        assert_eq!(post, expected);
+        assert_eq!(post.map_err(|e| e.error), expected);
 
         // This should work!
         let post = post_dummy_request(requester.clone(), Some(reward));
@@ -40,6 +86,7 @@ fn test_post_request() {
             WitnetEvent::<Test>::PostedRequest {
                 request_id: 0,
                 requester: 7,
+                assigned_operator: None,
             }
             .into(),
         );
@@ -66,6 +113,7 @@ fn test_post_request() {
             WitnetEvent::<Test>::PostedRequest {
                 request_id: 1,
                 requester: 7,
+                assigned_operator: None,
             }
             .into(),
         );
@@ -80,9 +128,9 @@ fn test_report_result() {
         let reporter = Origin::signed(reporter_id);
         let requester_id = 7;
         let requester = Origin::signed(requester_id);
-        let max_byte_size = usize::from(MAX_WITNET_BYTE_SIZE);
+        let max_byte_size = usize::from(MAX_RESULT_SIZE);
 
-        <Test as WitnetConfig>::TimeProvider::set_timestamp(1000);
+        <Test as WitnetConfig>::TimeProvider::set_timestamp(500);
 
         let initial_requester_free_balance =
             <Test as WitnetConfig>::Currency::free_balance(&requester_id);
@@ -94,63 +142,76 @@ fn test_report_result() {
             <Test as WitnetConfig>::Currency::reserved_balance(&reporter_id);
 
         post_dummy_request(requester.clone(), Some(reward)).ok();
+        <Test as WitnetConfig>::TimeProvider::set_timestamp(1000);
 
         // This should fail because account #7 is not allowed to report
-        let report = Witnet::report_result(
+        let report = report_result(
             requester,
             0,
             999,
             [0; 32],
             vec![0; max_byte_size],
+            ResultStatus::Success,
         );
         let expected = Err(WitnetError::<Test>::UnauthorizedOperator.into());
         assert_eq!(report, expected);
 
         // This should fail because we are reporting a result from the future
-        let report = Witnet::report_result(
+        let report = report_result(
             reporter.clone(),
             0,
             1000,
             [0; 32],
             vec![0; max_byte_size],
+            ResultStatus::Success,
         );
         let expected = Err(WitnetError::<Test>::ResultFromFuture.into());
         assert_eq!(report, expected);
 
         // This should fail because the result cannot be empty
-        let report = Witnet::report_result(reporter.clone(), 0, 999, [0; 32], vec![]);
+        let report = report_result(
+            reporter.clone(),
+            0,
+            999,
+            [0; 32],
+            vec![],
+            ResultStatus::Success,
+        );
         let expected = Err(WitnetError::<Test>::EmptyResult.into());
         assert_eq!(report, expected);
 
         // This should fail because the result is oversized
-        let report = Witnet::report_result(
+        let report = report_result(
             reporter.clone(),
             0,
             999,
             [0; 32],
             vec![0; max_byte_size + 1],
+            ResultStatus::Success,
         );
         let expected = Err(WitnetError::<Test>::OversizedResult.into());
         assert_eq!(report, expected);
 
         // This should fail because the request is unknown
-        let report = Witnet::report_result(
+        let report = report_result(
             reporter.clone(),
             1,
             999,
             [0; 32],
             vec![0; max_byte_size],
+            ResultStatus::Success,
         );
         let expected = Err(WitnetError::<Test>::UnknownRequest.into());
         assert_eq!(report, expected);
 
         // This should work!
-        let report = Witnet::report_result(
+        let report = report_result(
             reporter.clone(),
             0,
             999,
             [0; 32],
             vec![0; max_byte_size],
+            ResultStatus::Success,
         );
         assert_ok!(report);
         System::assert_last_event(
@@ -179,12 +240,13 @@ fn test_report_result() {
 
 
         // This should fail because it is a duplicated report
-        let report = Witnet::report_result(
+        let report = report_result(
             reporter,
             0,
             999,
             [0; 32],
             vec![0; max_byte_size],
+            ResultStatus::Success,
         );
         let expected = Err(WitnetError::<Test>::AlreadyReported.into());
         assert_eq!(report, expected);
@@ -204,21 +266,24 @@ fn test_operators() {
         let genesis_operator = Origin::signed(5);
         let account_seven = Origin::signed(7);
         let account_nine = Origin::signed(9);
-        let max_byte_size = usize::from(MAX_WITNET_BYTE_SIZE);
+        let max_byte_size = usize::from(MAX_RESULT_SIZE);
 
-        <Test as WitnetConfig>::TimeProvider::set_timestamp(1000);
+        <Test as WitnetConfig>::TimeProvider::set_timestamp(500);
 
         post_dummy_request(account_seven.clone(), None).ok();
         post_dummy_request(account_seven.clone(), None).ok();
         post_dummy_request(account_seven.clone(), None).ok();
 
+        <Test as WitnetConfig>::TimeProvider::set_timestamp(1000);
+
         // This should fail because account #7 is not allowed to report yet
-        let report = Witnet::report_result(
+        let report = report_result(
             account_seven.clone(),
             0,
             999,
             [0; 32],
             vec![0; max_byte_size],
+            ResultStatus::Success,
         );
         let expected = Err(WitnetError::<Test>::UnauthorizedOperator.into());
         assert_eq!(report, expected);
@@ -245,12 +310,13 @@ fn test_operators() {
         );
 
         // The recently added operator should now be able to report
-        let report = Witnet::report_result(
+        let report = report_result(
             account_seven.clone(),
             0,
             999,
             [0; 32],
             vec![0; max_byte_size],
+            ResultStatus::Success,
         );
         assert_ok!(report);
         System::assert_last_event(
@@ -273,12 +339,13 @@ fn test_operators() {
         );
 
         // This second new operator should also be able to report
-        let report = Witnet::report_result(
+        let report = report_result(
             account_nine.clone(),
             1,
             999,
             [0; 32],
             vec![0; max_byte_size],
+            ResultStatus::Success,
         );
         assert_ok!(report);
         System::assert_last_event(
@@ -306,23 +373,25 @@ fn test_operators() {
         );
 
         // The recently removed operator should no longer be able to report
-        let report = Witnet::report_result(
+        let report = report_result(
             account_seven,
             2,
             999,
             [0; 32],
             vec![0; max_byte_size],
+            ResultStatus::Success,
         );
         let expected = Err(WitnetError::<Test>::UnauthorizedOperator.into());
         assert_eq!(report, expected);
 
         // But the third operator (account #9) should still be able to report
-        let report = Witnet::report_result(
+        let report = report_result(
             account_nine,
             2,
             999,
             [0; 32],
             vec![0; max_byte_size],
+            ResultStatus::Success,
         );
         assert_ok!(report);
         System::assert_last_event(
@@ -339,3 +408,2716 @@ fn test_operators() {
         assert_eq!(remove, expected);
     })
 }
+
+/// Build the XCM message that delivers the result `[1, 2, 3]`, reported with timestamp 999.
+fn expected_xcm(request_id: RequestId, call_index: [u8; 2]) -> Xcm<()> {
+    let response: Response<Test> = (999, [1; 32], vec![1, 2, 3].try_into().unwrap());
+    let mut call = call_index.to_vec();
+    (request_id, response).encode_to(&mut call);
+    let fee_asset: MultiAsset = (MultiLocation::parent(), XCM_FEE_ASSET_AMOUNT).into();
+
+    Xcm(vec![
+        WithdrawAsset(fee_asset.clone().into()),
+        BuyExecution {
+            fees: fee_asset,
+            weight_limit: Unlimited,
+        },
+        Transact {
+            origin_type: OriginKind::Xcm,
+            require_weight_at_most: 1_000_000_000,
+            call: call.into(),
+        },
+    ])
+}
+
+fn run_to_block(n: u64) {
+    while System::block_number() < n {
+        let next = System::block_number() + 1;
+        System::set_block_number(next);
+        Witnet::on_initialize(next);
+    }
+}
+
+/// Encode a length delimited protobuf field, as long as it is shorter than 128 bytes.
+fn protobuf_field(number: u8, payload: &[u8]) -> Vec<u8> {
+    let mut field = vec![(number << 3) | 2, payload.len() as u8];
+    field.extend_from_slice(payload);
+    field
+}
+
+/// Bound the arguments of a template.
+fn template_args(args: Vec<Vec<u8>>) -> TemplateArgs<Test> {
+    let args: Vec<BoundedVec<u8, <Test as WitnetConfig>::MaxRequestSize>> = args
+        .into_iter()
+        .map(|arg| arg.try_into().unwrap())
+        .collect();
+
+    args.try_into().unwrap()
+}
+
+/// Build a Witnet data request with a single data source.
+fn witnet_request(url: &[u8]) -> Vec<u8> {
+    // A `RADRetrieve` with an HTTP GET kind, the URL and a CBOR script
+    let mut retrieve = vec![1 << 3, 1];
+    retrieve.extend(protobuf_field(2, url));
+    retrieve.extend(protobuf_field(3, &[0x80]));
+    // A `DataRequestOutput` with a `RADRequest` and a witness reward
+    let mut request = protobuf_field(1, &protobuf_field(2, &retrieve));
+    request.extend([2 << 3, 0xe8, 0x07]);
+    request
+}
+
+/// The `sendRequest` JSON-RPC call for `witnet_request(b"https://api.example.com")`.
+#[cfg(feature = "ocw")]
+const SEND_REQUEST_CALL: &str = concat!(
+    r#"{"jsonrpc":"2.0","id":1,"method":"sendRequest","params":{"dro":{"data_request":"#,
+    r#"{"time_lock":0,"retrieve":[{"kind":"HTTP-GET","url":"https://api.example.com","#,
+    r#""script":[128],"body":[],"headers":[]}],"aggregate":{"filters":[],"reducer":0},"#,
+    r#""tally":{"filters":[],"reducer":0}},"witness_reward":1000,"witnesses":0,"#,
+    r#""commit_and_reveal_fee":0,"min_consensus_percentage":0,"collateral":0},"fee":1}}"#,
+);
+
+type OldRequestEntry = (
+    Option<(Vec<u8>, u64)>,
+    Option<(u64, [u8; 32], Vec<u8>)>,
+    Option<u64>,
+);
+
+fn last_assigned_operator() -> Option<u64> {
+    System::events()
+        .into_iter()
+        .rev()
+        .find_map(|record| match record.event {
+            Event::Witnet(WitnetEvent::<Test>::PostedRequest {
+                assigned_operator, ..
+            }) => Some(assigned_operator),
+            _ => None,
+        })
+        .flatten()
+}
+
+#[test]
+fn test_subscriptions() {
+    ExtBuilder::default().build_and_execute(|| {
+        let owner_id = 7;
+        let owner = Origin::signed(owner_id);
+        let reward = 100;
+        let budget = 250;
+        let period = 3;
+
+        // This should fail because the period cannot be zero
+        let subscribe = Witnet::subscribe(owner.clone(), reward, budget, 0, vec![]);
+        let expected = Err(WitnetError::<Test>::InvalidSubscriptionPeriod.into());
+        assert_eq!(subscribe, expected);
+
+        // This should fail because subscriptions without a reward would never end
+        let subscribe = Witnet::subscribe(owner.clone(), 0, budget, period, vec![]);
+        let expected = Err(WitnetError::<Test>::UnderpayingRequest.into());
+        assert_eq!(subscribe, expected);
+
+        // This should fail because the budget does not even cover the first request
+        let subscribe = Witnet::subscribe(owner.clone(), reward, reward - 1, period, vec![]);
+        let expected = Err(WitnetError::<Test>::InsufficientSubscriptionBudget.into());
+        assert_eq!(subscribe, expected);
+
+        // This should work!
+        let subscribe = Witnet::subscribe(owner.clone(), reward, budget, period, vec![]);
+        assert_ok!(subscribe);
+        System::assert_last_event(
+            WitnetEvent::<Test>::Subscribed {
+                subscription_id: 0,
+                owner: owner_id,
+            }
+            .into(),
+        );
+        assert_eq!(
+            <Test as WitnetConfig>::Currency::reserved_balance(&owner_id),
+            budget
+        );
+
+        // The first request is posted in the next block
+        run_to_block(2);
+        System::assert_last_event(
+            WitnetEvent::<Test>::TriggeredSubscription {
+                subscription_id: 0,
+                request_id: 0,
+            }
+            .into(),
+        );
+
+        // Nothing happens until the period has elapsed
+        run_to_block(4);
+        let post = post_dummy_request(owner.clone(), None);
+        assert_ok!(post);
+        System::assert_last_event(
+            WitnetEvent::<Test>::PostedRequest {
+                request_id: 1,
+                requester: owner_id,
+                assigned_operator: None,
+            }
+            .into(),
+        );
+
+        // The second request exhausts the budget, and the remainder is given back
+        run_to_block(5);
+        System::assert_has_event(
+            WitnetEvent::<Test>::TriggeredSubscription {
+                subscription_id: 0,
+                request_id: 2,
+            }
+            .into(),
+        );
+        System::assert_last_event(
+            WitnetEvent::<Test>::ExhaustedSubscription { subscription_id: 0 }.into(),
+        );
+        assert_eq!(
+            <Test as WitnetConfig>::Currency::reserved_balance(&owner_id),
+            2 * reward
+        );
+
+        // An exhausted subscription cannot be cancelled
+        let cancel = Witnet::cancel_subscription(owner.clone(), 0);
+        let expected = Err(WitnetError::<Test>::UnknownSubscription.into());
+        assert_eq!(cancel, expected);
+
+        // A subscription can only be cancelled by its owner
+        assert_ok!(Witnet::subscribe(
+            owner.clone(),
+            reward,
+            budget,
+            period,
+            vec![]
+        ));
+        let cancel = Witnet::cancel_subscription(Origin::signed(9), 1);
+        let expected = Err(WitnetError::<Test>::NotSubscriptionOwner.into());
+        assert_eq!(cancel, expected);
+
+        // Cancelling gives back the whole remaining budget, and stops posting requests
+        let cancel = Witnet::cancel_subscription(owner, 1);
+        assert_ok!(cancel);
+        System::assert_last_event(
+            WitnetEvent::<Test>::CancelledSubscription { subscription_id: 1 }.into(),
+        );
+        assert_eq!(
+            <Test as WitnetConfig>::Currency::reserved_balance(&owner_id),
+            2 * reward
+        );
+        run_to_block(10);
+        System::assert_last_event(
+            WitnetEvent::<Test>::CancelledSubscription { subscription_id: 1 }.into(),
+        );
+    });
+}
+
+#[test]
+fn test_feeds() {
+    ExtBuilder::default().build_and_execute(|| {
+        let reporter = Origin::signed(5);
+        let requester = Origin::signed(7);
+        let bytes = vec![1, 2, 3];
+        let feed_id = feed_id_for(&bytes);
+
+        <Test as WitnetConfig>::TimeProvider::set_timestamp(500);
+
+        // Nothing has been reported yet
+        assert_eq!(Witnet::latest_value(&feed_id), None);
+        assert_eq!(
+            Witnet::fresh_value(&feed_id, 1000),
+            Err(WitnetError::<Test>::UnknownFeed.into())
+        );
+
+        // Post two requests for the same feed, and one that is not part of any feed
+        assert_ok!(Witnet::post_feed_request(
+            requester.clone(),
+            0,
+            bytes.clone()
+        ));
+        assert_ok!(Witnet::post_feed_request(
+            requester.clone(),
+            0,
+            bytes.clone()
+        ));
+        assert_ok!(Witnet::post_request(requester, 0, bytes));
+        <Test as WitnetConfig>::TimeProvider::set_timestamp(1000);
+
+        // Reporting the second request updates the feed
+        assert_ok!(Witnet::report_result(
+            reporter.clone(),
+            1,
+            900,
+            [1; 32],
+            vec![1],
+            ResultStatus::Success,
+        ));
+        System::assert_last_event(
+            WitnetEvent::<Test>::PostedResult {
+                request_id: 1,
+                reporter: 5,
+            }
+            .into(),
+        );
+        System::assert_has_event(
+            WitnetEvent::<Test>::UpdatedFeed {
+                feed_id,
+                request_id: 1,
+            }
+            .into(),
+        );
+        let expected = (1, (900, [1; 32], vec![1].try_into().unwrap()));
+        assert_eq!(Witnet::latest_value(&feed_id), Some(expected.clone()));
+
+        // Reporting an older result for the first request does not override the feed
+        assert_ok!(Witnet::report_result(
+            reporter.clone(),
+            0,
+            800,
+            [0; 32],
+            vec![0],
+            ResultStatus::Success,
+        ));
+        assert_eq!(Witnet::latest_value(&feed_id), Some(expected.clone()));
+
+        // Requests that are not part of a feed do not update it
+        assert_ok!(Witnet::report_result(
+            reporter,
+            2,
+            950,
+            [2; 32],
+            vec![2],
+            ResultStatus::Success
+        ));
+        assert_eq!(Witnet::latest_value(&feed_id), Some(expected.clone()));
+
+        // Staleness is checked against the age of the latest value
+        assert_eq!(Witnet::fresh_value(&feed_id, 100), Ok(expected));
+        assert_eq!(
+            Witnet::fresh_value(&feed_id, 99),
+            Err(WitnetError::<Test>::StaleFeed.into())
+        );
+    });
+}
+
+#[test]
+fn test_result_freshness() {
+    ExtBuilder::default().build_and_execute(|| {
+        let reporter = Origin::signed(5);
+        let requester = Origin::signed(7);
+
+        <Test as WitnetConfig>::TimeProvider::set_timestamp(10_000);
+        post_dummy_request(requester.clone(), None).ok();
+        <Test as WitnetConfig>::TimeProvider::set_timestamp(20_000);
+        post_dummy_request(requester, None).ok();
+
+        // This should fail because the result is older than the request
+        let report = report_result(
+            reporter.clone(),
+            0,
+            9_999,
+            [0; 32],
+            vec![0],
+            ResultStatus::Success,
+        );
+        let expected = Err(WitnetError::<Test>::ResultPredatesRequest.into());
+        assert_eq!(report, expected);
+
+        // Results produced right when the request was posted are fine
+        let report = report_result(
+            reporter.clone(),
+            0,
+            10_000,
+            [0; 32],
+            vec![0],
+            ResultStatus::Success,
+        );
+        assert_ok!(report);
+
+        <Test as WitnetConfig>::TimeProvider::set_timestamp(100_000);
+
+        // This should fail because the result is older than the maximum result age
+        let report = report_result(
+            reporter.clone(),
+            1,
+            100_000 - MAX_RESULT_AGE - 1,
+            [0; 32],
+            vec![0],
+            ResultStatus::Success,
+        );
+        let expected = Err(WitnetError::<Test>::ResultTooOld.into());
+        assert_eq!(report, expected);
+
+        // Results that are exactly as old as the maximum result age are fine
+        let report = report_result(
+            reporter,
+            1,
+            100_000 - MAX_RESULT_AGE,
+            [0; 32],
+            vec![0],
+            ResultStatus::Success,
+        );
+        assert_ok!(report);
+    });
+}
+
+#[test]
+fn test_request_expiry() {
+    ExtBuilder::default().build_and_execute(|| {
+        let reward = 123;
+        let reporter = Origin::signed(5);
+        let requester_id = 7;
+        let requester = Origin::signed(requester_id);
+
+        <Test as WitnetConfig>::TimeProvider::set_timestamp(1000);
+
+        // This should fail because the timeout cannot be zero
+        let post = Witnet::post_request_with_timeout(requester.clone(), reward, vec![], 0);
+        let expected = Err(WitnetError::<Test>::InvalidTimeout.into());
+        assert_eq!(post, expected);
+
+        // Post one request with a custom timeout, and another one with the default timeout
+        assert_ok!(Witnet::post_request_with_timeout(
+            requester.clone(),
+            reward,
+            vec![],
+            3
+        ));
+        assert_ok!(post_dummy_request(requester, Some(reward)));
+        assert_eq!(
+            <Test as WitnetConfig>::Currency::reserved_balance(&requester_id),
+            2 * reward
+        );
+
+        // The first request is still open right before its deadline
+        run_to_block(3);
+        assert_eq!(
+            <Test as WitnetConfig>::Currency::reserved_balance(&requester_id),
+            2 * reward
+        );
+
+        // The first request expires at its deadline, and its reward is given back
+        run_to_block(4);
+        System::assert_last_event(WitnetEvent::<Test>::ExpiredRequest { request_id: 0 }.into());
+        assert_eq!(
+            <Test as WitnetConfig>::Currency::reserved_balance(&requester_id),
+            reward
+        );
+
+        // This should fail because the request has expired
+        <Test as WitnetConfig>::TimeProvider::set_timestamp(2000);
+        let report = report_result(
+            reporter.clone(),
+            0,
+            1999,
+            [0; 32],
+            vec![0],
+            ResultStatus::Success,
+        );
+        let expected = Err(WitnetError::<Test>::RequestExpired.into());
+        assert_eq!(report, expected);
+
+        // The second request can still be reported, and it should not expire later
+        assert_ok!(Witnet::report_result(
+            reporter,
+            1,
+            1999,
+            [0; 32],
+            vec![0],
+            ResultStatus::Success
+        ));
+        run_to_block(101);
+        System::assert_last_event(
+            WitnetEvent::<Test>::PostedResult {
+                request_id: 1,
+                reporter: 5,
+            }
+            .into(),
+        );
+        assert_eq!(
+            <Test as WitnetConfig>::Currency::reserved_balance(&requester_id),
+            0
+        );
+    });
+}
+
+#[test]
+fn test_lazy_expiry() {
+    ExtBuilder::default().build_and_execute(|| {
+        let reward = 10;
+        let requester_id = 7;
+        let requester = Origin::signed(requester_id);
+
+        // Fill the expiry queue for all the blocks that a request could be queued for
+        <Test as WitnetConfig>::TimeProvider::set_timestamp(500);
+        for account in 0..20 {
+            let spammer = Origin::signed(account % 10);
+            assert_ok!(Witnet::post_request_with_timeout(spammer, 0, vec![], 3));
+        }
+
+        // Requests can still be posted, but they will only expire lazily
+        assert_ok!(Witnet::post_request_with_timeout(
+            requester.clone(),
+            reward,
+            vec![],
+            3
+        ));
+
+        // This should fail because the deadline has not passed yet
+        let expire = Witnet::expire_request(requester.clone(), 20);
+        let expected = Err(WitnetError::<Test>::RequestNotDue.into());
+        assert_eq!(expire, expected);
+
+        // Once the deadline has passed, the request can no longer be reported
+        run_to_block(4);
+        <Test as WitnetConfig>::TimeProvider::set_timestamp(1000);
+        let report = Witnet::report_result(
+            Origin::signed(5),
+            20,
+            999,
+            [0; 32],
+            vec![0],
+            ResultStatus::Success,
+        );
+        let expected = Err(WitnetError::<Test>::RequestExpired.into());
+        assert_eq!(report, expected);
+        assert_eq!(
+            <Test as WitnetConfig>::Currency::reserved_balance(&requester_id),
+            reward
+        );
+
+        // Anyone can then expire it, which gives the reward back
+        assert_ok!(Witnet::expire_request(Origin::signed(9), 20));
+        System::assert_last_event(WitnetEvent::<Test>::ExpiredRequest { request_id: 20 }.into());
+        assert_eq!(
+            <Test as WitnetConfig>::Currency::reserved_balance(&requester_id),
+            0
+        );
+        let expire = Witnet::expire_request(requester, 20);
+        let expected = Err(WitnetError::<Test>::RequestExpired.into());
+        assert_eq!(expire, expected);
+    });
+}
+
+#[test]
+fn test_pending_requests() {
+    ExtBuilder::default().build_and_execute(|| {
+        let reporter = Origin::signed(5);
+        let requester = Origin::signed(7);
+
+        <Test as WitnetConfig>::TimeProvider::set_timestamp(500);
+
+        assert_eq!(Witnet::pending_requests_count(), 0);
+        assert_eq!(Witnet::pending_requests(None, 10), Vec::<RequestId>::new());
+
+        // Post a few requests, one of which expires soon
+        for _ in 0..4 {
+            post_dummy_request(requester.clone(), None).ok();
+        }
+        assert_ok!(Witnet::post_request_with_timeout(requester, 0, vec![], 1));
+        assert_eq!(Witnet::pending_requests_count(), 5);
+        <Test as WitnetConfig>::TimeProvider::set_timestamp(1000);
+
+        // Reported and expired requests are no longer pending
+        assert_ok!(Witnet::report_result(
+            reporter,
+            1,
+            999,
+            [0; 32],
+            vec![0],
+            ResultStatus::Success
+        ));
+        run_to_block(2);
+        assert_eq!(Witnet::pending_requests_count(), 3);
+
+        // Paginating through the index yields every pending request exactly once
+        let mut pending = Vec::new();
+        let mut start_after = None;
+        loop {
+            let page = Witnet::pending_requests(start_after, 2);
+            if page.is_empty() {
+                break;
+            }
+            assert!(page.len() <= 2);
+            start_after = page.last().copied();
+            pending.extend(page);
+        }
+        pending.sort_unstable();
+        assert_eq!(pending, vec![0, 2, 3]);
+    });
+}
+
+#[test]
+fn test_migrate_to_v1() {
+    ExtBuilder::default().build_and_execute(|| {
+        // Post two requests, and resolve one of them
+        <Test as WitnetConfig>::TimeProvider::set_timestamp(500);
+        assert_ok!(post_dummy_request(Origin::signed(7), None));
+        assert_ok!(post_dummy_request(Origin::signed(7), None));
+        <Test as WitnetConfig>::TimeProvider::set_timestamp(1000);
+        assert_ok!(Witnet::report_result(
+            Origin::signed(5),
+            0,
+            999,
+            [0; 32],
+            vec![0],
+            ResultStatus::Success
+        ));
+
+        // Make it look as if pending requests were never indexed
+        StorageVersion::new(0).put::<Witnet>();
+        frame_support::migration::remove_storage_prefix(b"Witnet", b"PendingRequests", b"");
+        frame_support::migration::put_storage_value(
+            b"Witnet",
+            b"CounterForPendingRequests",
+            b"",
+            0u32,
+        );
+        assert_eq!(Witnet::pending_requests(None, 10), Vec::<RequestId>::new());
+
+        Witnet::on_runtime_upgrade();
+        run_to_block(4);
+        assert_eq!(Witnet::on_chain_storage_version(), StorageVersion::new(4));
+
+        // Only the request that is still waiting for a result gets indexed
+        assert_eq!(Witnet::pending_requests(None, 10), vec![1]);
+        assert_eq!(Witnet::pending_requests_count(), 1);
+    });
+}
+
+#[test]
+fn test_remote_requests() {
+    ExtBuilder::default().build_and_execute(|| {
+        let reward = 123;
+        let reporter_id = 5;
+        let reporter = Origin::signed(reporter_id);
+        let sibling_id = SIBLING_ACCOUNT_OFFSET + u64::from(SIBLING_PARA_ID);
+        let sibling = Origin::signed(sibling_id);
+        let sibling_location = MultiLocation::new(1, X1(Parachain(SIBLING_PARA_ID)));
+
+        <Test as WitnetConfig>::TimeProvider::set_timestamp(500);
+
+        // This should fail because only other chains can post remote requests
+        let post = Witnet::post_remote_request(Origin::signed(7), reward, vec![]);
+        assert_eq!(post, Err(DispatchError::BadOrigin));
+
+        // This should fail because the reward does not cover the XCM fees
+        let post = Witnet::post_remote_request(sibling.clone(), XCM_FEE - 1, vec![]);
+        let expected = Err(WitnetError::<Test>::UnderpayingCallback.into());
+        assert_eq!(post, expected);
+
+        // This should fail because there is no callback for receiving results on the other chain
+        let other_sibling = Origin::signed(SIBLING_ACCOUNT_OFFSET + 3_000);
+        let post = Witnet::post_remote_request(other_sibling, reward, vec![]);
+        let expected = Err(WitnetError::<Test>::BadCallbackDestination.into());
+        assert_eq!(post, expected);
+
+        // This should work, and the reward is paid by the sovereign account of the sibling
+        assert_ok!(Witnet::post_remote_request(sibling, reward, vec![]));
+        System::assert_last_event(
+            WitnetEvent::<Test>::PostedRequest {
+                request_id: 0,
+                requester: sibling_id,
+                assigned_operator: None,
+            }
+            .into(),
+        );
+        assert_eq!(
+            <Test as WitnetConfig>::Currency::reserved_balance(&sibling_id),
+            reward
+        );
+
+        // Reporting the result sends it back to the sibling through XCM
+        let initial_reporter_free_balance =
+            <Test as WitnetConfig>::Currency::free_balance(&reporter_id);
+        <Test as WitnetConfig>::TimeProvider::set_timestamp(1000);
+        assert_ok!(Witnet::report_result(
+            reporter.clone(),
+            0,
+            999,
+            [1; 32],
+            vec![1, 2, 3],
+            ResultStatus::Success,
+        ));
+        System::assert_has_event(
+            WitnetEvent::<Test>::DeliveredResult {
+                request_id: 0,
+                destination: sibling_location.clone(),
+            }
+            .into(),
+        );
+        assert_eq!(
+            sent_xcm(),
+            vec![(sibling_location, expected_xcm(0, XCM_CALLBACK_INDEX))]
+        );
+
+        // The XCM fees are paid out of the reward
+        assert_eq!(
+            <Test as WitnetConfig>::Currency::free_balance(&XCM_FEE_COLLECTOR),
+            XCM_FEE
+        );
+        assert_eq!(
+            <Test as WitnetConfig>::Currency::free_balance(&reporter_id)
+                - initial_reporter_free_balance,
+            reward - XCM_FEE
+        );
+        assert_eq!(
+            <Test as WitnetConfig>::Currency::reserved_balance(&sibling_id),
+            0
+        );
+
+        // Results of local requests are not sent anywhere
+        post_dummy_request(Origin::signed(7), None).ok();
+        assert_ok!(Witnet::report_result(
+            reporter,
+            1,
+            999,
+            [1; 32],
+            vec![1],
+            ResultStatus::Success
+        ));
+        assert_eq!(sent_xcm().len(), 1);
+    });
+}
+
+#[test]
+fn test_request_deposits() {
+    ExtBuilder::default().build_and_execute(|| {
+        let reward = 5;
+        let reporter = Origin::signed(5);
+        let requester_id = 7;
+        let requester = Origin::signed(requester_id);
+        let bytes = vec![0; 10];
+
+        <Test as WitnetConfig>::TimeProvider::set_timestamp(500);
+
+        // Posting reserves both the reward and a deposit that depends on the size of the request
+        assert_ok!(Witnet::post_request(
+            requester.clone(),
+            reward,
+            bytes.clone()
+        ));
+        let deposit = request_deposit::<Test>(bytes.len());
+        assert_eq!(deposit, 10);
+        assert_eq!(
+            <Test as WitnetConfig>::Currency::reserved_balance(&requester_id),
+            reward + deposit
+        );
+
+        // This should fail because the request is still waiting for a result
+        let prune = Witnet::prune_request(requester.clone(), 0);
+        let expected = Err(WitnetError::<Test>::RequestStillPending.into());
+        assert_eq!(prune, expected);
+
+        // Once reported, only the deposit remains reserved
+        <Test as WitnetConfig>::TimeProvider::set_timestamp(1000);
+        assert_ok!(Witnet::report_result(
+            reporter.clone(),
+            0,
+            999,
+            [0; 32],
+            vec![0],
+            ResultStatus::Success,
+        ));
+        assert_eq!(
+            <Test as WitnetConfig>::Currency::reserved_balance(&requester_id),
+            deposit
+        );
+
+        // This should fail because only the requester can prune the request
+        let prune = Witnet::prune_request(Origin::signed(9), 0);
+        let expected = Err(WitnetError::<Test>::NotRequester.into());
+        assert_eq!(prune, expected);
+
+        // Pruning gives back the deposit
+        assert_ok!(Witnet::prune_request(requester.clone(), 0));
+        System::assert_last_event(WitnetEvent::<Test>::PrunedRequest { request_id: 0 }.into());
+        assert_eq!(
+            <Test as WitnetConfig>::Currency::reserved_balance(&requester_id),
+            0
+        );
+
+        // This should fail because the request is gone
+        let report = report_result(
+            reporter.clone(),
+            0,
+            999,
+            [0; 32],
+            vec![0],
+            ResultStatus::Success,
+        );
+        let expected = Err(WitnetError::<Test>::UnknownRequest.into());
+        assert_eq!(report, expected);
+        let prune = Witnet::prune_request(requester.clone(), 0);
+        let expected = Err(WitnetError::<Test>::UnknownRequest.into());
+        assert_eq!(prune, expected);
+    });
+}
+
+#[test]
+fn test_max_open_requests() {
+    ExtBuilder::default().build_and_execute(|| {
+        let reporter = Origin::signed(5);
+        let requester = Origin::signed(7);
+
+        <Test as WitnetConfig>::TimeProvider::set_timestamp(500);
+
+        for _ in 0..MAX_OPEN_REQUESTS {
+            assert_ok!(post_dummy_request(requester.clone(), None));
+        }
+
+        // This should fail because the requester has too many open requests
+        let post = post_dummy_request(requester.clone(), None);
+        let expected = Err(WitnetError::<Test>::TooManyOpenRequests.into());
+        assert_eq!(post, expected);
+
+        // Other accounts are not affected
+        assert_ok!(post_dummy_request(Origin::signed(9), None));
+
+        // Once one of the requests is resolved, there is room for a new one
+        <Test as WitnetConfig>::TimeProvider::set_timestamp(1000);
+        assert_ok!(Witnet::report_result(
+            reporter,
+            0,
+            999,
+            [0; 32],
+            vec![0],
+            ResultStatus::Success
+        ));
+        assert_ok!(post_dummy_request(requester, None));
+    });
+}
+
+#[test]
+fn test_operator_info() {
+    ExtBuilder::default().build_and_execute(|| {
+        let genesis_operator = Origin::signed(5);
+        let name = b"Witnet Foundation".to_vec();
+        let witnet_pkh = [7; 20];
+        let url = b"https://relayer.witnet.io".to_vec();
+
+        // Genesis operators are listed as having joined at genesis
+        let expected = vec![(5, OperatorInfo::default())];
+        assert_eq!(Witnet::operators(), expected);
+
+        // This should fail because only operators can set operator info
+        let set =
+            Witnet::set_operator_info(Origin::signed(7), name.clone(), witnet_pkh, url.clone());
+        let expected = Err(WitnetError::<Test>::UnauthorizedOperator.into());
+        assert_eq!(set, expected);
+
+        // This should fail because the name is too long
+        let set = Witnet::set_operator_info(
+            genesis_operator.clone(),
+            vec![0; 33],
+            witnet_pkh,
+            url.clone(),
+        );
+        let expected = Err(WitnetError::<Test>::OversizedOperatorInfo.into());
+        assert_eq!(set, expected);
+
+        // This should work!
+        assert_ok!(Witnet::set_operator_info(
+            genesis_operator.clone(),
+            name.clone(),
+            witnet_pkh,
+            url.clone()
+        ));
+        System::assert_last_event(WitnetEvent::<Test>::UpdatedOperatorInfo { operator: 5 }.into());
+        let info = OperatorInfo {
+            name,
+            witnet_pkh,
+            url,
+            joined_at: 0,
+        };
+        assert_eq!(Witnet::operators(), vec![(5, info.clone())]);
+
+        // Newly added operators are listed as having joined in the current block
+        System::set_block_number(3);
+        assert_ok!(Witnet::add_operator(genesis_operator.clone(), 7));
+        let mut operators = Witnet::operators();
+        operators.sort_by_key(|(operator, _)| *operator);
+        let new_operator_info = OperatorInfo {
+            joined_at: 3,
+            ..Default::default()
+        };
+        assert_eq!(operators, vec![(5, info.clone()), (7, new_operator_info)]);
+
+        // Removed operators are no longer listed
+        assert_ok!(Witnet::remove_operator(genesis_operator, 7));
+        assert_eq!(Witnet::operators(), vec![(5, info)]);
+    });
+}
+
+#[test]
+fn test_operator_stats() {
+    ExtBuilder::default().build_and_execute(|| {
+        let reward = 50;
+        let genesis_operator = Origin::signed(5);
+        let requester = Origin::signed(7);
+
+        // Operators start with empty statistics, and other accounts have none at all
+        assert_eq!(Witnet::operator_stats(&5), Some(OperatorStats::default()));
+        assert_eq!(Witnet::operator_stats(&7), None);
+
+        // Add an operator that will never report anything
+        assert_ok!(Witnet::add_operator(genesis_operator.clone(), 6));
+
+        // Reporting keeps track of the reward and of the blocks elapsed since posting the request
+        <Test as WitnetConfig>::TimeProvider::set_timestamp(500);
+        assert_ok!(Witnet::post_request(requester.clone(), reward, vec![]));
+        run_to_block(4);
+        <Test as WitnetConfig>::TimeProvider::set_timestamp(1000);
+        assert_ok!(Witnet::report_result(
+            genesis_operator.clone(),
+            0,
+            999,
+            [0; 32],
+            vec![0],
+            ResultStatus::Success,
+        ));
+        let stats = Witnet::operator_stats(&5).unwrap();
+        let expected = OperatorStats {
+            reports: 1,
+            rewards: reward,
+            total_latency: 3,
+            disputes_lost: 0,
+            last_report_at: Some(4),
+        };
+        assert_eq!(stats, expected);
+        assert_eq!(stats.average_latency(), 3);
+
+        // This should fail because only the dispute origin can record lost disputes
+        let record = Witnet::record_lost_dispute(Origin::signed(7), 5);
+        assert_eq!(record, Err(DispatchError::BadOrigin));
+
+        // This should fail because account #7 is not an operator
+        let record = Witnet::record_lost_dispute(Origin::root(), 7);
+        let expected = Err(WitnetError::<Test>::UnknownOperator.into());
+        assert_eq!(record, expected);
+
+        // This should work!
+        assert_ok!(Witnet::record_lost_dispute(Origin::root(), 5));
+        System::assert_last_event(WitnetEvent::<Test>::LostDispute { operator: 5 }.into());
+        assert_eq!(Witnet::operator_stats(&5).unwrap().disputes_lost, 1);
+
+        // Nobody is inactive yet after the first inactivity period
+        run_to_block(INACTIVITY_PERIOD);
+        assert!(Witnet::operator_stats(&6).is_some());
+
+        // Report a result in the very same block as the request was posted
+        run_to_block(INACTIVITY_PERIOD + 50);
+        <Test as WitnetConfig>::TimeProvider::set_timestamp(1500);
+        assert_ok!(Witnet::post_request(requester, reward, vec![]));
+        <Test as WitnetConfig>::TimeProvider::set_timestamp(2000);
+        assert_ok!(Witnet::report_result(
+            genesis_operator,
+            1,
+            1999,
+            [0; 32],
+            vec![0],
+            ResultStatus::Success,
+        ));
+        let stats = Witnet::operator_stats(&5).unwrap();
+        assert_eq!(stats.reports, 2);
+        assert_eq!(stats.rewards, 2 * reward);
+        assert_eq!(stats.average_latency(), 1);
+
+        // Operators that have not reported anything for a whole period get rotated out
+        run_to_block(2 * INACTIVITY_PERIOD);
+        System::assert_last_event(
+            WitnetEvent::<Test>::RemovedInactiveOperator { operator: 6 }.into(),
+        );
+        assert_eq!(Witnet::operator_stats(&6), None);
+
+        // The last operator is never rotated out, even if inactive
+        run_to_block(3 * INACTIVITY_PERIOD);
+        assert!(Witnet::operator_stats(&5).is_some());
+    });
+}
+
+#[test]
+fn test_exclusive_assignments() {
+    ExtBuilder::default().build_and_execute(|| {
+        ExclusivityWindow::set(5);
+        let requester = Origin::signed(7);
+        let genesis_operator = Origin::signed(5);
+        assert_ok!(Witnet::add_operator(genesis_operator.clone(), 8));
+        assert_ok!(Witnet::add_operator(genesis_operator, 9));
+
+        // Operators take turns in being assigned requests
+        <Test as WitnetConfig>::TimeProvider::set_timestamp(500);
+        let mut assigned = vec![];
+        for _ in 0..4 {
+            assert_ok!(post_dummy_request(requester.clone(), None));
+            assigned.push(last_assigned_operator().unwrap());
+        }
+        let mut first_turn = assigned[..3].to_vec();
+        first_turn.sort();
+        assert_eq!(first_turn, vec![5, 8, 9]);
+        assert_eq!(assigned[3], assigned[0]);
+
+        // This should fail because request #0 is assigned to a different operator
+        <Test as WitnetConfig>::TimeProvider::set_timestamp(1000);
+        let report = report_result(
+            Origin::signed(assigned[1]),
+            0,
+            999,
+            [0; 32],
+            vec![0],
+            ResultStatus::Success,
+        );
+        let expected = Err(WitnetError::<Test>::NotAssignedOperator.into());
+        assert_eq!(report, expected);
+
+        // This should work!
+        assert_ok!(Witnet::report_result(
+            Origin::signed(assigned[0]),
+            0,
+            999,
+            [0; 32],
+            vec![0],
+            ResultStatus::Success,
+        ));
+
+        // Requests assigned to a removed operator can be reported by anyone else right away
+        assert_ok!(Witnet::remove_operator(
+            Origin::signed(assigned[0]),
+            assigned[2]
+        ));
+        assert_ok!(Witnet::report_result(
+            Origin::signed(assigned[1]),
+            2,
+            999,
+            [0; 32],
+            vec![0],
+            ResultStatus::Success,
+        ));
+
+        // Once the window is over, any operator can report
+        run_to_block(6);
+        assert_ok!(Witnet::report_result(
+            Origin::signed(assigned[0]),
+            1,
+            999,
+            [0; 32],
+            vec![0],
+            ResultStatus::Success,
+        ));
+    });
+}
+
+#[test]
+fn test_operators_bounds() {
+    ExtBuilder::default().build_and_execute(|| {
+        let genesis_operator = Origin::signed(5);
+        assert_eq!(Witnet::operators_count(), 1);
+
+        // Fill the operator set up to the maximum
+        for operator in 6..(5 + u64::from(MAX_OPERATORS)) {
+            assert_ok!(Witnet::add_operator(genesis_operator.clone(), operator));
+        }
+        assert_eq!(Witnet::operators_count(), MAX_OPERATORS);
+
+        // This should fail because there is no room for more operators
+        let add = Witnet::add_operator(genesis_operator.clone(), 9);
+        let expected = Err(WitnetError::<Test>::TooManyOperators.into());
+        assert_eq!(add, expected);
+
+        // Adding an account that is already an operator does not need any room
+        assert_ok!(Witnet::add_operator(genesis_operator.clone(), 6));
+        assert_eq!(Witnet::operators_count(), MAX_OPERATORS);
+
+        // Removing operators is fine as long as enough of them remain
+        MinOperators::set(MAX_OPERATORS - 1);
+        assert_ok!(Witnet::remove_operator(genesis_operator.clone(), 6));
+        assert_eq!(Witnet::operators_count(), MAX_OPERATORS - 1);
+
+        // This should fail because removing one more operator would leave too few of them
+        let remove = Witnet::remove_operator(genesis_operator, 7);
+        let expected = Err(WitnetError::<Test>::TooFewOperators.into());
+        assert_eq!(remove, expected);
+    });
+}
+
+#[test]
+fn test_migrate_to_v2() {
+    ExtBuilder::default().build_and_execute(|| {
+        // Make it look as if the operators were never counted
+        StorageVersion::new(1).put::<Witnet>();
+        frame_support::migration::put_storage_value(b"Witnet", b"CounterForOperators", b"", 0u32);
+        assert_eq!(Witnet::operators_count(), 0);
+
+        Witnet::on_runtime_upgrade();
+        assert_eq!(Witnet::operators_count(), 1);
+        assert_eq!(Witnet::on_chain_storage_version(), StorageVersion::new(1));
+
+        // The rest of the migrations go through requests, subscriptions and feeds in the next
+        // blocks, even if there are none
+        run_to_block(3);
+        assert_eq!(Witnet::on_chain_storage_version(), StorageVersion::new(4));
+
+        // Migrations do not run twice
+        frame_support::migration::put_storage_value(b"Witnet", b"CounterForOperators", b"", 0u32);
+        Witnet::on_runtime_upgrade();
+        assert_eq!(Witnet::operators_count(), 0);
+    });
+}
+
+#[test]
+fn test_pause() {
+    ExtBuilder::default().build_and_execute(|| {
+        let reward = 50;
+        let reporter_id = 5;
+        let reporter = Origin::signed(reporter_id);
+        let requester_id = 7;
+        let requester = Origin::signed(requester_id);
+        let posting = PauseFlags {
+            posting: true,
+            ..Default::default()
+        };
+        let reporting = PauseFlags {
+            reporting: true,
+            ..Default::default()
+        };
+        let payouts = PauseFlags {
+            payouts: true,
+            ..Default::default()
+        };
+
+        // This should fail because only the pause origin can pause the pallet
+        let pause = Witnet::pause(requester.clone(), posting);
+        assert_eq!(pause, Err(DispatchError::BadOrigin));
+
+        // This should fail because posting is paused
+        assert_ok!(Witnet::pause(Origin::root(), posting));
+        System::assert_last_event(
+            WitnetEvent::<Test>::Paused {
+                operations: posting,
+            }
+            .into(),
+        );
+        let post = post_dummy_request(requester.clone(), Some(reward));
+        let expected = Err(WitnetError::<Test>::Paused.into());
+        assert_eq!(post, expected);
+        assert_eq!(
+            <Test as WitnetConfig>::Currency::reserved_balance(&requester_id),
+            0
+        );
+
+        // Once resumed, posting works again
+        assert_ok!(Witnet::unpause(Origin::root(), posting));
+        System::assert_last_event(
+            WitnetEvent::<Test>::Unpaused {
+                operations: posting,
+            }
+            .into(),
+        );
+        <Test as WitnetConfig>::TimeProvider::set_timestamp(500);
+        assert_ok!(post_dummy_request(requester.clone(), Some(reward)));
+        assert_ok!(post_dummy_request(requester, Some(reward)));
+
+        // This should fail because reporting is paused
+        <Test as WitnetConfig>::TimeProvider::set_timestamp(1000);
+        assert_ok!(Witnet::pause(Origin::root(), reporting));
+        let report = report_result(
+            reporter.clone(),
+            0,
+            999,
+            [0; 32],
+            vec![0],
+            ResultStatus::Success,
+        );
+        let expected = Err(WitnetError::<Test>::Paused.into());
+        assert_eq!(report, expected);
+        assert_ok!(Witnet::unpause(Origin::root(), reporting));
+
+        // While payouts are paused, results are stored but the reward stays reserved
+        assert_ok!(Witnet::pause(Origin::root(), payouts));
+        let reporter_balance = <Test as WitnetConfig>::Currency::free_balance(&reporter_id);
+        assert_ok!(Witnet::report_result(
+            reporter.clone(),
+            0,
+            999,
+            [0; 32],
+            vec![0],
+            ResultStatus::Success,
+        ));
+        assert_eq!(
+            <Test as WitnetConfig>::Currency::free_balance(&reporter_id),
+            reporter_balance
+        );
+        assert_eq!(
+            <Test as WitnetConfig>::Currency::reserved_balance(&requester_id),
+            2 * reward
+        );
+
+        // This should fail because payouts are still paused
+        let claim = Witnet::claim_payout(reporter.clone(), 0);
+        let expected = Err(WitnetError::<Test>::Paused.into());
+        assert_eq!(claim, expected);
+
+        // Once payouts are resumed, anyone can claim the deferred payout
+        assert_ok!(Witnet::unpause(Origin::root(), payouts));
+        assert_ok!(Witnet::claim_payout(Origin::signed(9), 0));
+        System::assert_last_event(WitnetEvent::<Test>::ClaimedPayout { request_id: 0 }.into());
+        assert_eq!(
+            <Test as WitnetConfig>::Currency::free_balance(&reporter_id),
+            reporter_balance + reward
+        );
+        assert_eq!(
+            <Test as WitnetConfig>::Currency::reserved_balance(&requester_id),
+            reward
+        );
+
+        // This should fail because the payout has already been made
+        let claim = Witnet::claim_payout(reporter, 0);
+        let expected = Err(WitnetError::<Test>::UnknownPayout.into());
+        assert_eq!(claim, expected);
+    });
+}
+
+#[test]
+fn test_error_results() {
+    ExtBuilder::default().build_and_execute(|| {
+        let reward = 100;
+        let reporter_id = 5;
+        let reporter = Origin::signed(reporter_id);
+        let requester_id = 7;
+        let requester = Origin::signed(requester_id);
+        let bytes = vec![1, 2, 3];
+        let feed_id = feed_id_for(&bytes);
+        let insufficient_consensus = 0x51;
+
+        <Test as WitnetConfig>::TimeProvider::set_timestamp(500);
+        assert_ok!(Witnet::post_feed_request(requester, reward, bytes));
+        let requester_balance = <Test as WitnetConfig>::Currency::free_balance(&requester_id);
+        let reporter_balance = <Test as WitnetConfig>::Currency::free_balance(&reporter_id);
+
+        // Reporting an error gives most of the reward back to the requester
+        <Test as WitnetConfig>::TimeProvider::set_timestamp(1000);
+        assert_ok!(Witnet::report_result(
+            reporter,
+            0,
+            999,
+            [0; 32],
+            vec![0xd8, 0x27, 0x81, 0x18, 0x51],
+            ResultStatus::Error(insufficient_consensus)
+        ));
+        System::assert_has_event(
+            WitnetEvent::<Test>::ReportedError {
+                request_id: 0,
+                error_code: insufficient_consensus,
+            }
+            .into(),
+        );
+        assert_eq!(
+            <Test as WitnetConfig>::Currency::free_balance(&requester_id),
+            requester_balance + 80
+        );
+        assert_eq!(
+            <Test as WitnetConfig>::Currency::free_balance(&reporter_id),
+            reporter_balance + 20
+        );
+        assert_eq!(Witnet::result_error(0), Some(insufficient_consensus));
+
+        // Errors never make it into feeds
+        assert_eq!(Witnet::latest_value(&feed_id), None);
+    });
+}
+
+#[test]
+fn test_hash_only_results() {
+    ExtBuilder::default().build_and_execute(|| {
+        let reporter = Origin::signed(5);
+        let requester = Origin::signed(7);
+        let result_bytes = vec![1, 2, 3];
+
+        <Test as WitnetConfig>::TimeProvider::set_timestamp(500);
+        assert_ok!(post_dummy_request(requester.clone(), None));
+        assert_ok!(post_dummy_request(requester, None));
+
+        // This should fail because the request is unknown
+        let claim = Witnet::claim_result(2, &result_bytes);
+        let expected = Err(WitnetError::<Test>::UnknownRequest.into());
+        assert_eq!(claim, expected);
+
+        // This should fail because the request has not been resolved yet
+        let claim = Witnet::claim_result(0, &result_bytes);
+        let expected = Err(WitnetError::<Test>::UnresolvedRequest.into());
+        assert_eq!(claim, expected);
+
+        // Results that are stored in full can be claimed as well
+        <Test as WitnetConfig>::TimeProvider::set_timestamp(1000);
+        assert_ok!(Witnet::report_result(
+            reporter.clone(),
+            0,
+            999,
+            [0; 32],
+            result_bytes.clone(),
+            ResultStatus::Success
+        ));
+        assert_eq!(Witnet::claim_result(0, &result_bytes), Ok((999, [0; 32])));
+
+        // In hash-only mode, the full result is only published through an event
+        HashOnlyResults::set(true);
+        assert_ok!(Witnet::report_result(
+            reporter,
+            1,
+            999,
+            [1; 32],
+            result_bytes.clone(),
+            ResultStatus::Success
+        ));
+        System::assert_has_event(
+            WitnetEvent::<Test>::PublishedResult {
+                request_id: 1,
+                result_bytes: result_bytes.clone(),
+            }
+            .into(),
+        );
+
+        // This should fail because the bytes do not match the hash of the result
+        let claim = Witnet::claim_result(1, &[3, 2, 1]);
+        let expected = Err(WitnetError::<Test>::ResultMismatch.into());
+        assert_eq!(claim, expected);
+
+        // This should work!
+        assert_eq!(Witnet::claim_result(1, &result_bytes), Ok((999, [1; 32])));
+
+        // Feeds only keep the hash of their values as well
+        let feed_bytes = vec![4, 5, 6];
+        let feed_id = feed_id_for(&feed_bytes);
+        assert_ok!(Witnet::post_feed_request(Origin::signed(7), 0, feed_bytes));
+        assert_ok!(Witnet::report_result(
+            Origin::signed(5),
+            2,
+            999,
+            [2; 32],
+            result_bytes.clone(),
+            ResultStatus::Success
+        ));
+        let stored = (999, [2; 32], Default::default());
+        assert_eq!(Witnet::latest_value(&feed_id), Some((2, stored)));
+        assert_eq!(Witnet::claim_result(2, &result_bytes), Ok((999, [2; 32])));
+    });
+}
+
+#[test]
+fn test_migrate_to_v3() {
+    ExtBuilder::default().build_and_execute(|| {
+        let reward = 10;
+        let requester_id = 7;
+        let oversized_request = vec![0; usize::from(MAX_REQUEST_SIZE) + 1];
+        let oversized_result = vec![1; usize::from(MAX_RESULT_SIZE) + 1];
+        let put_old_request = |request_id: RequestId, entry: OldRequestEntry| {
+            let key = Twox64Concat::hash(&request_id.encode());
+            frame_support::migration::put_storage_value(b"Witnet", b"Requests", &key, entry);
+        };
+
+        // Make it look as if requests and results were bounded by a single, larger limit
+        StorageVersion::new(2).put::<Witnet>();
+        <Test as WitnetConfig>::TimeProvider::set_timestamp(500);
+        assert_ok!(post_dummy_request(
+            Origin::signed(requester_id),
+            Some(reward)
+        ));
+        put_old_request(
+            0,
+            (Some((oversized_request, reward)), None, Some(requester_id)),
+        );
+        put_old_request(
+            1,
+            (None, Some((999, [1; 32], oversized_result.clone())), None),
+        );
+
+        Witnet::on_runtime_upgrade();
+        assert_eq!(Witnet::on_chain_storage_version(), StorageVersion::new(2));
+
+        // Entries that do not fit cannot be decoded until they get migrated
+        <Test as WitnetConfig>::TimeProvider::set_timestamp(1000);
+        let report = Witnet::report_result(
+            Origin::signed(5),
+            0,
+            999,
+            [0; 32],
+            vec![0],
+            ResultStatus::Success,
+        );
+        let expected = Err(WitnetError::<Test>::UnknownRequest.into());
+        assert_eq!(report, expected);
+
+        // Both requests get migrated in the next block, and then subscriptions and feeds
+        run_to_block(2);
+        assert_eq!(Witnet::on_chain_storage_version(), StorageVersion::new(2));
+        run_to_block(4);
+        assert_eq!(Witnet::on_chain_storage_version(), StorageVersion::new(4));
+
+        // Requests that no longer fit expire, and their rewards are given back
+        System::assert_has_event(WitnetEvent::<Test>::ExpiredRequest { request_id: 0 }.into());
+        assert_eq!(Witnet::pending_requests_count(), 0);
+        assert_eq!(
+            <Test as WitnetConfig>::Currency::reserved_balance(&requester_id),
+            0
+        );
+
+        // Results that no longer fit are kept as a hash
+        assert_eq!(
+            Witnet::claim_result(1, &oversized_result),
+            Ok((999, [1; 32]))
+        );
+    });
+}
+
+#[test]
+fn test_templates() {
+    ExtBuilder::default().build_and_execute(|| {
+        let reward = 10;
+        let owner_id = 7;
+        let owner = Origin::signed(owner_id);
+        let requester_id = 8;
+        let requester = Origin::signed(requester_id);
+        let bytes = witnet_request(b"https://api.example.com");
+        let template_id = sp_io::hashing::blake2_256(&bytes);
+
+        // This should fail because the template does not exist yet
+        let post = Witnet::post_request_from_template(
+            requester.clone(),
+            template_id,
+            reward,
+            Default::default(),
+        );
+        let expected = Err(WitnetError::<Test>::UnknownTemplate.into());
+        assert_eq!(post, expected);
+
+        // These should fail because the templates are not Witnet data requests
+        let register = Witnet::register_template(owner.clone(), vec![0; 10]);
+        let expected = Err(WitnetError::<Test>::InvalidTemplate.into());
+        assert_eq!(register, expected);
+        let no_sources = protobuf_field(1, &[]);
+        let register = Witnet::register_template(owner.clone(), no_sources);
+        assert_eq!(register, expected);
+
+        // Registering a template reserves the same deposit as a request of the same size
+        assert_ok!(Witnet::register_template(owner.clone(), bytes.clone()));
+        System::assert_last_event(
+            WitnetEvent::<Test>::RegisteredTemplate {
+                template_id,
+                owner: owner_id,
+            }
+            .into(),
+        );
+        assert_eq!(
+            <Test as WitnetConfig>::Currency::reserved_balance(&owner_id),
+            request_deposit::<Test>(bytes.len())
+        );
+
+        // This should fail because the template has already been registered
+        let register = Witnet::register_template(owner, bytes.clone());
+        let expected = Err(WitnetError::<Test>::TemplateAlreadyRegistered.into());
+        assert_eq!(register, expected);
+
+        // Requests posted from a template do not pay for storing a copy of its bytes
+        assert_ok!(Witnet::post_request_from_template(
+            requester,
+            template_id,
+            reward,
+            Default::default()
+        ));
+        assert_eq!(
+            <Test as WitnetConfig>::Currency::reserved_balance(&requester_id),
+            reward + request_deposit::<Test>(0)
+        );
+        assert_eq!(Witnet::request_bytes(0), Some(bytes));
+    });
+}
+
+#[test]
+fn test_template_arguments() {
+    ExtBuilder::default().build_and_execute(|| {
+        let reward = 10;
+        let requester = Origin::signed(8);
+        let template = witnet_request(b"https://api.example.com/ticker/\\0\\-\\1\\");
+        let template_id = sp_io::hashing::blake2_256(&template);
+        assert_ok!(Witnet::register_template(Origin::signed(7), template));
+
+        // This should fail because the placeholders are left unfilled
+        let post = Witnet::post_request_from_template(
+            requester.clone(),
+            template_id,
+            reward,
+            Default::default(),
+        );
+        let expected = Err(WitnetError::<Test>::InvalidTemplateArguments.into());
+        assert_eq!(post, expected);
+
+        // This should fail because the second placeholder has no argument
+        let args = template_args(vec![b"BTC".to_vec()]);
+        let post = Witnet::post_request_from_template(requester.clone(), template_id, reward, args);
+        let expected = Err(WitnetError::<Test>::InvalidTemplateArguments.into());
+        assert_eq!(post, expected);
+
+        // This should fail because the filled request is too big, even if every argument fits
+        let args = template_args(vec![
+            vec![b'A'; usize::from(MAX_REQUEST_SIZE)],
+            b"USD".to_vec(),
+        ]);
+        let post = Witnet::post_request_from_template(requester.clone(), template_id, reward, args);
+        let expected = Err(WitnetError::<Test>::OversizedRequest.into());
+        assert_eq!(post, expected);
+
+        // This should work, and the request is stored in full
+        let args = template_args(vec![b"BTC".to_vec(), b"USD".to_vec()]);
+        assert_ok!(Witnet::post_request_from_template(
+            requester,
+            template_id,
+            reward,
+            args
+        ));
+        let expected = witnet_request(b"https://api.example.com/ticker/BTC-USD");
+        assert_eq!(Witnet::request_bytes(0), Some(expected));
+    });
+}
+
+#[cfg(feature = "ocw")]
+#[test]
+fn test_offchain_relayer() {
+    use crate::{
+        mock::{Call, Extrinsic},
+        ocw::DEFAULT_NODE_URL,
+    };
+    use codec::Decode;
+    use sp_core::offchain::{
+        testing::{PendingRequest, TestOffchainExt, TestTransactionPoolExt},
+        OffchainDbExt, OffchainWorkerExt, TransactionPoolExt,
+    };
+
+    let (offchain, offchain_state) = TestOffchainExt::new();
+    let (pool, pool_state) = TestTransactionPoolExt::new();
+    let mut ext = ExtBuilder::default().build();
+    ext.register_extension(OffchainDbExt::new(offchain.clone()));
+    ext.register_extension(OffchainWorkerExt::new(offchain));
+    ext.register_extension(TransactionPoolExt::new(pool));
+    // The keystore holds the key of account #5, which is an operator
+    UintAuthorityId::set_all_keys(vec![5]);
+
+    let expect_call = |body: &str, response: &str| {
+        offchain_state.write().expect_request(PendingRequest {
+            method: "POST".into(),
+            uri: DEFAULT_NODE_URL.into(),
+            headers: vec![("Content-Type".into(), "application/json".into())],
+            body: body.as_bytes().to_vec(),
+            response: Some(response.as_bytes().to_vec()),
+            sent: true,
+            ..Default::default()
+        });
+    };
+    let dr_tx_hash = [0xab; 32];
+    let dr_tx_hash_hex = "ab".repeat(32);
+    let block_hash_hex = "cd".repeat(32);
+    let report_call = format!(
+        r#"{{"jsonrpc":"2.0","id":1,"method":"dataRequestReport","params":["{}"]}}"#,
+        dr_tx_hash_hex
+    );
+    let report_response = format!(
+        concat!(
+            r#"{{"jsonrpc":"2.0","id":1,"result":{{"tally":{{"tally":[24,42]}},"#,
+            r#""block_hash_tally_tx":"{}"}}}}"#,
+        ),
+        block_hash_hex
+    );
+    let block_call = format!(
+        r#"{{"jsonrpc":"2.0","id":1,"method":"getBlock","params":["{}"]}}"#,
+        block_hash_hex
+    );
+    let block_response = |confirmed: bool| {
+        format!(
+            concat!(
+                r#"{{"jsonrpc":"2.0","id":1,"result":{{"block_header":{{"beacon":"#,
+                r#"{{"checkpoint":100}}}},"confirmed":{}}}}}"#,
+            ),
+            confirmed
+        )
+    };
+
+    ext.execute_with(|| {
+        System::set_block_number(1);
+        <Test as WitnetConfig>::TimeProvider::set_timestamp(500);
+        let bytes = witnet_request(b"https://api.example.com");
+        assert_ok!(Witnet::post_request(Origin::signed(7), 10, bytes));
+
+        // The request gets sent to the Witnet node, and nothing gets reported yet
+        expect_call(
+            SEND_REQUEST_CALL,
+            &format!(
+                r#"{{"jsonrpc":"2.0","id":1,"result":"{}"}}"#,
+                dr_tx_hash_hex
+            ),
+        );
+        Witnet::offchain_worker(1);
+        assert!(pool_state.read().transactions.is_empty());
+
+        // The data request is not resolved yet, so nothing gets reported either
+        expect_call(
+            &report_call,
+            r#"{"jsonrpc":"2.0","id":1,"result":{"tally":null}}"#,
+        );
+        Witnet::offchain_worker(2);
+        assert!(pool_state.read().transactions.is_empty());
+
+        // The tally is not final until its block gets confirmed by a superblock
+        expect_call(&report_call, &report_response);
+        expect_call(&block_call, &block_response(false));
+        Witnet::offchain_worker(3);
+        assert!(pool_state.read().transactions.is_empty());
+
+        // Once final, the tally gets reported in a signed transaction, timestamped with the
+        // checkpoint of its block
+        expect_call(&report_call, &report_response);
+        expect_call(&block_call, &block_response(true));
+        Witnet::offchain_worker(4);
+        let tx = pool_state.write().transactions.pop().unwrap();
+        assert!(pool_state.read().transactions.is_empty());
+        let tx = Extrinsic::decode(&mut &*tx).unwrap();
+        assert_eq!(tx.signature, Some((0, ())));
+        assert_eq!(
+            tx.call,
+            Call::Witnet(crate::Call::report_result {
+                request_id: 0,
+                timestamp: crate::witnet::checkpoint_timestamp_millis(100),
+                dr_tx_hash,
+                result_bytes: vec![24, 42],
+                status: ResultStatus::Success,
+            })
+        );
+    });
+}
+
+#[test]
+fn test_unsigned_results() {
+    ExtBuilder::default().build_and_execute(|| {
+        let reward = 50;
+        let operator_id = 5;
+        let operator_key = UintAuthorityId(operator_id);
+        let requester_id = 7;
+        <Test as WitnetConfig>::TimeProvider::set_timestamp(500);
+        assert_ok!(post_dummy_request(
+            Origin::signed(requester_id),
+            Some(reward)
+        ));
+        <Test as WitnetConfig>::TimeProvider::set_timestamp(1000);
+
+        // This should fail because only operators can set operator keys
+        let set_key = Witnet::set_operator_key(Origin::signed(requester_id), operator_key.clone());
+        let expected = Err(WitnetError::<Test>::UnauthorizedOperator.into());
+        assert_eq!(set_key, expected);
+
+        let payload = ResultPayload::<Test> {
+            operator: operator_id,
+            request_id: 0,
+            timestamp: 600,
+            dr_tx_hash: [1; 32],
+            result_bytes: vec![24, 42],
+            status: ResultStatus::Success,
+        };
+        let signature = operator_key.sign(&payload.signing_bytes()).unwrap();
+        let call = crate::Call::report_result_unsigned {
+            payload: payload.clone(),
+            signature: signature.clone(),
+        };
+        let validate = || Witnet::validate_unsigned(TransactionSource::External, &call);
+        let validate_payload = |payload: ResultPayload<Test>| {
+            let signature = operator_key.sign(&payload.signing_bytes()).unwrap();
+            let call = crate::Call::report_result_unsigned { payload, signature };
+            Witnet::validate_unsigned(TransactionSource::External, &call)
+        };
+
+        // This should fail because the operator has not set its key yet
+        assert_eq!(validate(), InvalidTransaction::BadSigner.into());
+
+        assert_ok!(Witnet::set_operator_key(
+            Origin::signed(operator_id),
+            operator_key.clone()
+        ));
+        System::assert_last_event(
+            WitnetEvent::<Test>::UpdatedOperatorKey {
+                operator: operator_id,
+            }
+            .into(),
+        );
+        let expected = ValidTransaction::with_tag_prefix("WitnetOracleResult")
+            .priority(UNSIGNED_PRIORITY)
+            .and_provides(0u64)
+            .longevity(5)
+            .propagate(true)
+            .build();
+        assert_eq!(validate(), expected);
+
+        // This should fail because the payload has been tampered with
+        let tampered = crate::Call::report_result_unsigned {
+            payload: ResultPayload {
+                result_bytes: vec![24, 43],
+                ..payload.clone()
+            },
+            signature: signature.clone(),
+        };
+        let validate_tampered = Witnet::validate_unsigned(TransactionSource::External, &tampered);
+        assert_eq!(validate_tampered, InvalidTransaction::BadProof.into());
+
+        // This should fail because the signature is not over the payload along with the prefix
+        // and the genesis hash
+        let unprefixed = crate::Call::report_result_unsigned {
+            payload: payload.clone(),
+            signature: operator_key.sign(&payload.encode()).unwrap(),
+        };
+        let validate_unprefixed =
+            Witnet::validate_unsigned(TransactionSource::External, &unprefixed);
+        assert_eq!(validate_unprefixed, InvalidTransaction::BadProof.into());
+
+        // Reports that would be rejected right away never get into the transaction pool
+        let from_future = ResultPayload {
+            timestamp: 1000,
+            ..payload.clone()
+        };
+        assert_eq!(
+            validate_payload(from_future),
+            InvalidTransaction::Future.into()
+        );
+        let oversized = ResultPayload {
+            result_bytes: vec![0; MAX_RESULT_SIZE as usize + 1],
+            ..payload.clone()
+        };
+        assert_eq!(
+            validate_payload(oversized),
+            InvalidTransaction::ExhaustsResources.into()
+        );
+        let reporting = PauseFlags {
+            reporting: true,
+            ..Default::default()
+        };
+        assert_ok!(Witnet::pause(Origin::root(), reporting));
+        assert_eq!(validate(), InvalidTransaction::Call.into());
+        assert_ok!(Witnet::unpause(Origin::root(), reporting));
+        <Test as WitnetConfig>::TimeProvider::set_timestamp(600 + MAX_RESULT_AGE + 1);
+        assert_eq!(validate(), InvalidTransaction::Stale.into());
+        <Test as WitnetConfig>::TimeProvider::set_timestamp(1000);
+
+        // This should fail because unsigned reports must come with no origin
+        let report = Witnet::report_result_unsigned(
+            Origin::signed(operator_id),
+            payload.clone(),
+            signature.clone(),
+        );
+        assert_eq!(report, Err(DispatchError::BadOrigin.into()));
+
+        // Unsigned reports are charged for the size of their result
+        let declared_weight = call.get_dispatch_info().weight;
+        assert_eq!(
+            declared_weight,
+            report_result_weight::<Test>(payload.result_bytes.len())
+        );
+
+        // The reward goes to the operator, which pays no fees
+        assert_ok!(Witnet::report_result_unsigned(
+            Origin::none(),
+            payload,
+            signature
+        ));
+        System::assert_last_event(
+            WitnetEvent::<Test>::PostedResult {
+                request_id: 0,
+                reporter: operator_id,
+            }
+            .into(),
+        );
+        assert_eq!(
+            <Test as WitnetConfig>::Currency::free_balance(&operator_id),
+            1_000 + reward
+        );
+
+        // The same report cannot be replayed once the request has been resolved
+        assert_eq!(validate(), InvalidTransaction::Stale.into());
+    });
+}
+
+#[cfg(feature = "ocw")]
+#[test]
+fn test_unsigned_offchain_relayer() {
+    use codec::Decode;
+    use sp_core::offchain::{
+        testing::{PendingRequest, TestOffchainExt, TestTransactionPoolExt},
+        OffchainDbExt, OffchainWorkerExt, TransactionPoolExt,
+    };
+
+    use crate::{
+        mock::{Call, Extrinsic},
+        ocw::{UnsignedWitnetNodeRelayer, DEFAULT_NODE_URL},
+    };
+
+    let (offchain, offchain_state) = TestOffchainExt::new();
+    let (pool, pool_state) = TestTransactionPoolExt::new();
+    let mut ext = ExtBuilder::default().build();
+    ext.register_extension(OffchainDbExt::new(offchain.clone()));
+    ext.register_extension(OffchainWorkerExt::new(offchain));
+    ext.register_extension(TransactionPoolExt::new(pool));
+    // The keystore holds the operator key of account #5, which is an operator
+    UintAuthorityId::set_all_keys(vec![5]);
+
+    let expect_call = |body: String, response: String| {
+        offchain_state.write().expect_request(PendingRequest {
+            method: "POST".into(),
+            uri: DEFAULT_NODE_URL.into(),
+            headers: vec![("Content-Type".into(), "application/json".into())],
+            body: body.into_bytes(),
+            response: Some(response.into_bytes()),
+            sent: true,
+            ..Default::default()
+        });
+    };
+    let dr_tx_hash = [0xab; 32];
+    let dr_tx_hash_hex = "ab".repeat(32);
+    let block_hash_hex = "cd".repeat(32);
+
+    ext.execute_with(|| {
+        System::set_block_number(1);
+        <Test as WitnetConfig>::TimeProvider::set_timestamp(500);
+        let bytes = witnet_request(b"https://api.example.com");
+        assert_ok!(Witnet::post_request(Origin::signed(7), 10, bytes));
+
+        // Nothing gets relayed until the operator sets its operator key
+        UnsignedWitnetNodeRelayer::<Test>::relay(1);
+        assert_ok!(Witnet::set_operator_key(
+            Origin::signed(5),
+            UintAuthorityId(5)
+        ));
+
+        expect_call(
+            SEND_REQUEST_CALL.into(),
+            format!(
+                r#"{{"jsonrpc":"2.0","id":1,"result":"{}"}}"#,
+                dr_tx_hash_hex
+            ),
+        );
+        UnsignedWitnetNodeRelayer::<Test>::relay(2);
+
+        // Once final, the tally gets reported in an unsigned transaction, on behalf of the
+        // operator
+        expect_call(
+            format!(
+                r#"{{"jsonrpc":"2.0","id":1,"method":"dataRequestReport","params":["{}"]}}"#,
+                dr_tx_hash_hex
+            ),
+            format!(
+                concat!(
+                    r#"{{"jsonrpc":"2.0","id":1,"result":{{"tally":{{"tally":[24,42]}},"#,
+                    r#""block_hash_tally_tx":"{}"}}}}"#,
+                ),
+                block_hash_hex
+            ),
+        );
+        expect_call(
+            format!(
+                r#"{{"jsonrpc":"2.0","id":1,"method":"getBlock","params":["{}"]}}"#,
+                block_hash_hex
+            ),
+            concat!(
+                r#"{"jsonrpc":"2.0","id":1,"result":{"block_header":{"beacon":"#,
+                r#"{"checkpoint":100}},"confirmed":true}}"#,
+            )
+            .into(),
+        );
+        UnsignedWitnetNodeRelayer::<Test>::relay(3);
+        let tx = pool_state.write().transactions.pop().unwrap();
+        assert!(pool_state.read().transactions.is_empty());
+        let tx = Extrinsic::decode(&mut &*tx).unwrap();
+        assert_eq!(tx.signature, None);
+
+        let payload = ResultPayload::<Test> {
+            operator: 5,
+            request_id: 0,
+            timestamp: crate::witnet::checkpoint_timestamp_millis(100),
+            dr_tx_hash,
+            result_bytes: vec![24, 42],
+            status: ResultStatus::Success,
+        };
+        let signature = UintAuthorityId(5).sign(&payload.signing_bytes()).unwrap();
+        assert_eq!(
+            tx.call,
+            Call::Witnet(crate::Call::report_result_unsigned { payload, signature })
+        );
+    });
+}
+
+#[test]
+fn test_fee_less_reports() {
+    ExtBuilder::default().build_and_execute(|| {
+        let reporter = Origin::signed(5);
+        <Test as WitnetConfig>::TimeProvider::set_timestamp(500);
+        assert_ok!(post_dummy_request(Origin::signed(7), None));
+        <Test as WitnetConfig>::TimeProvider::set_timestamp(1000);
+
+        // Successful reports pay no fees
+        let report = Witnet::report_result(
+            reporter.clone(),
+            0,
+            600,
+            [1; 32],
+            vec![24, 42],
+            ResultStatus::Success,
+        );
+        assert_eq!(report.map(|info| info.pays_fee), Ok(Pays::No));
+
+        // Failing reports still pay
+        let report = Witnet::report_result(
+            reporter,
+            0,
+            600,
+            [1; 32],
+            vec![24, 42],
+            ResultStatus::Success,
+        );
+        let expected = DispatchErrorWithPostInfo {
+            post_info: PostDispatchInfo {
+                actual_weight: Some(failed_report_result_weight::<Test>(2)),
+                pays_fee: Pays::Yes,
+            },
+            error: WitnetError::<Test>::AlreadyReported.into(),
+        };
+        assert_eq!(report, Err(expected));
+    });
+}
+
+#[test]
+fn test_actual_weights() {
+    ExtBuilder::default().build_and_execute(|| {
+        let requester_id = 7;
+        let requester = Origin::signed(requester_id);
+        let reporter_id = 5;
+        let max_request_size = usize::from(MAX_REQUEST_SIZE);
+        let max_result_size = usize::from(MAX_RESULT_SIZE);
+
+        // Calls are charged up front as if they processed as many bytes as they can take
+        let post_call = crate::Call::<Test>::post_request {
+            reward: 10,
+            bytes: vec![1, 2, 3],
+        };
+        let declared_post_weight = post_call.get_dispatch_info().weight;
+        assert_eq!(
+            declared_post_weight,
+            post_request_weight::<Test>(max_request_size)
+        );
+        let report_call = crate::Call::<Test>::report_result {
+            request_id: 0,
+            timestamp: 600,
+            dr_tx_hash: [1; 32],
+            result_bytes: vec![24, 42],
+            status: ResultStatus::Success,
+        };
+        let declared_report_weight = report_call.get_dispatch_info().weight;
+        assert_eq!(
+            declared_report_weight,
+            report_result_weight::<Test>(max_result_size)
+        );
+
+        // But they only pay for the bytes that they actually process, and for the storage that
+        // they touch before failing, which is never more than they were charged for
+        let check_post = |post: DispatchResultWithPostInfo, len: usize| {
+            let (actual_weight, expected_weight) = match &post {
+                Ok(info) => (info.actual_weight, post_request_weight::<Test>(len)),
+                Err(e) => (
+                    e.post_info.actual_weight,
+                    failed_post_request_weight::<Test>(len),
+                ),
+            };
+            assert_eq!(actual_weight, Some(expected_weight));
+            assert!(expected_weight <= declared_post_weight);
+
+            post.map(|_| ()).map_err(|e| e.error)
+        };
+        let post = |origin: Origin, reward: BalanceFor<Test>, bytes: Vec<u8>| {
+            let len = bytes.len();
+            check_post(Witnet::post_request(origin, reward, bytes), len)
+        };
+        let check_report = |report: DispatchResultWithPostInfo, declared: Weight, len: usize| {
+            let (actual_weight, expected_weight) = match &report {
+                Ok(info) => (info.actual_weight, report_result_weight::<Test>(len)),
+                Err(e) => (
+                    e.post_info.actual_weight,
+                    failed_report_result_weight::<Test>(len),
+                ),
+            };
+            assert_eq!(actual_weight, Some(expected_weight));
+            assert!(expected_weight <= declared);
+
+            report.map(|_| ()).map_err(|e| e.error)
+        };
+        let report = |operator: u64,
+                      request_id: RequestId,
+                      timestamp: u64,
+                      result_bytes: Vec<u8>,
+                      status: ResultStatus| {
+            let len = result_bytes.len();
+            let report = Witnet::report_result(
+                Origin::signed(operator),
+                request_id,
+                timestamp,
+                [1; 32],
+                result_bytes,
+                status,
+            );
+            check_report(report, declared_report_weight, len)
+        };
+        // Unsigned reports are charged for the size of their result right away
+        let report_unsigned = |operator: u64,
+                               request_id: RequestId,
+                               timestamp: u64,
+                               result_bytes: Vec<u8>,
+                               status: ResultStatus| {
+            let payload = ResultPayload::<Test> {
+                operator,
+                request_id,
+                timestamp,
+                dr_tx_hash: [1; 32],
+                result_bytes,
+                status,
+            };
+            let len = payload.result_bytes.len();
+            let signature = UintAuthorityId(operator)
+                .sign(&payload.signing_bytes())
+                .unwrap();
+            let call = crate::Call::<Test>::report_result_unsigned {
+                payload: payload.clone(),
+                signature: signature.clone(),
+            };
+            let declared = call.get_dispatch_info().weight;
+            assert_eq!(declared, report_result_weight::<Test>(len));
+            let report = Witnet::report_result_unsigned(Origin::none(), payload, signature);
+            check_report(report, declared, len)
+        };
+        let report_fails = |operator: u64,
+                            request_id: RequestId,
+                            timestamp: u64,
+                            result_bytes: Vec<u8>,
+                            error: WitnetError<Test>| {
+            let success = ResultStatus::Success;
+            let signed = report(
+                operator,
+                request_id,
+                timestamp,
+                result_bytes.clone(),
+                success,
+            );
+            let unsigned = report_unsigned(operator, request_id, timestamp, result_bytes, success);
+            assert_eq!(signed, Err(error.into()));
+            assert_eq!(unsigned, signed);
+        };
+
+        // Posting succeeds
+        <Test as WitnetConfig>::TimeProvider::set_timestamp(500);
+        assert_ok!(post(requester.clone(), 10, vec![1, 2, 3]));
+        assert!(post_request_weight::<Test>(3) < declared_post_weight);
+
+        // Posting fails because the request is too large
+        let oversized = post(requester.clone(), 10, vec![0; max_request_size + 1]);
+        let expected = Err(WitnetError::<Test>::OversizedRequest.into());
+        assert_eq!(oversized, expected);
+
+        // Posting fails because the reward cannot be reserved
+        let unfunded = post(requester.clone(), 1_000_000, vec![1, 2, 3]);
+        let expected = Err(pallet_balances::Error::<Test>::InsufficientBalance.into());
+        assert_eq!(unfunded, expected);
+
+        // Posting fails because the storage deposit cannot be reserved after the reward is
+        let underfunded = post(Origin::signed(9), 999, vec![1, 2, 3]);
+        let expected = Err(pallet_balances::Error::<Test>::InsufficientBalance.into());
+        assert_eq!(underfunded, expected);
+
+        // Posting fails because the requester has too many open requests
+        for _ in 0..MAX_OPEN_REQUESTS {
+            assert_ok!(post(Origin::signed(8), 0, vec![]));
+        }
+        let flooding = post(Origin::signed(8), 0, vec![]);
+        let expected = Err(WitnetError::<Test>::TooManyOpenRequests.into());
+        assert_eq!(flooding, expected);
+
+        // Posting fails because posting is paused
+        let posting = PauseFlags {
+            posting: true,
+            ..Default::default()
+        };
+        assert_ok!(Witnet::pause(Origin::root(), posting));
+        let paused = post(requester.clone(), 10, vec![1, 2, 3]);
+        assert_eq!(paused, Err(WitnetError::<Test>::Paused.into()));
+        assert_ok!(Witnet::unpause(Origin::root(), posting));
+
+        // Requests whose results go elsewhere than storage are posted in their own ways
+        let feed_request_id = crate::NextRequestId::<Test>::get();
+        assert_ok!(Witnet::post_feed_request(requester.clone(), 10, vec![1]));
+        let remote_request_id = crate::NextRequestId::<Test>::get();
+        let sibling = Origin::signed(SIBLING_ACCOUNT_OFFSET + u64::from(SIBLING_PARA_ID));
+        assert_ok!(Witnet::post_remote_request(sibling, XCM_FEE + 10, vec![]));
+        let randomness_request_id = crate::NextRequestId::<Test>::get();
+        assert_ok!(Witnet::request_randomness(requester, 10));
+
+        // Reporting fails in every way that it can, be it signed or unsigned
+        <Test as WitnetConfig>::TimeProvider::set_timestamp(1000);
+        let result = vec![24, 42];
+        let oversized_result = vec![0; max_result_size + 1];
+        report_fails(
+            reporter_id,
+            99,
+            600,
+            result.clone(),
+            WitnetError::UnknownRequest,
+        );
+        report_fails(6, 0, 600, result.clone(), WitnetError::UnauthorizedOperator);
+        report_fails(
+            reporter_id,
+            0,
+            1000,
+            result.clone(),
+            WitnetError::ResultFromFuture,
+        );
+        report_fails(reporter_id, 0, 600, vec![], WitnetError::EmptyResult);
+        report_fails(
+            reporter_id,
+            0,
+            600,
+            oversized_result,
+            WitnetError::OversizedResult,
+        );
+        report_fails(
+            reporter_id,
+            0,
+            400,
+            result.clone(),
+            WitnetError::ResultPredatesRequest,
+        );
+
+        <Test as WitnetConfig>::TimeProvider::set_timestamp(MAX_RESULT_AGE + 1000);
+        report_fails(
+            reporter_id,
+            0,
+            600,
+            result.clone(),
+            WitnetError::ResultTooOld,
+        );
+        <Test as WitnetConfig>::TimeProvider::set_timestamp(1000);
+
+        let reporting = PauseFlags {
+            reporting: true,
+            ..Default::default()
+        };
+        assert_ok!(Witnet::pause(Origin::root(), reporting));
+        report_fails(reporter_id, 0, 600, result.clone(), WitnetError::Paused);
+        assert_ok!(Witnet::unpause(Origin::root(), reporting));
+
+        assert_ok!(Witnet::add_operator(Origin::signed(reporter_id), 6));
+        crate::Assignments::<Test>::insert(0, (6, 100));
+        report_fails(
+            reporter_id,
+            0,
+            600,
+            result.clone(),
+            WitnetError::NotAssignedOperator,
+        );
+        crate::Assignments::<Test>::remove(0);
+
+        crate::Deadlines::<Test>::insert(1, 1);
+        report_fails(
+            reporter_id,
+            1,
+            600,
+            result.clone(),
+            WitnetError::RequestExpired,
+        );
+
+        // Reporting succeeds, which is charged for the bytes of the result, no matter where the
+        // result goes afterwards
+        let success = ResultStatus::Success;
+        assert_ok!(report(reporter_id, 0, 600, result.clone(), success));
+        assert!(report_result_weight::<Test>(2) < declared_report_weight);
+        report_fails(
+            reporter_id,
+            0,
+            600,
+            result.clone(),
+            WitnetError::AlreadyReported,
+        );
+
+        let error = ResultStatus::Error(1);
+        assert_ok!(report_unsigned(reporter_id, 2, 600, result.clone(), error));
+
+        HashOnlyResults::set(true);
+        assert_ok!(report(reporter_id, 3, 600, result.clone(), success));
+        HashOnlyResults::set(false);
+
+        assert_ok!(report(
+            reporter_id,
+            feed_request_id,
+            600,
+            result.clone(),
+            success
+        ));
+
+        assert_ok!(report_unsigned(
+            reporter_id,
+            remote_request_id,
+            600,
+            result,
+            success
+        ));
+        assert_eq!(sent_xcm().len(), 1);
+
+        let random_bytes = [&[0x58, 0x20][..], &[8; 32][..]].concat();
+        assert_ok!(report(
+            reporter_id,
+            randomness_request_id,
+            600,
+            random_bytes,
+            success
+        ));
+        assert!(crate::PendingRandomness::<Test>::get().is_some());
+    });
+}
+
+#[test]
+fn test_request_ownership() {
+    ExtBuilder::default().build_and_execute(|| {
+        let requester_id = 7;
+        let requester = Origin::signed(requester_id);
+        let new_owner_id = 8;
+        let new_owner = Origin::signed(new_owner_id);
+        let bytes = vec![0; 10];
+        let deposit = request_deposit::<Test>(bytes.len());
+
+        <Test as WitnetConfig>::TimeProvider::set_timestamp(500);
+        assert_ok!(Witnet::post_request(requester.clone(), 5, bytes));
+
+        // This should fail because the reward of the request is still reserved from the requester
+        let transfer = Witnet::transfer_request(requester.clone(), 0, new_owner_id);
+        let expected = Err(WitnetError::<Test>::RequestStillPending.into());
+        assert_eq!(transfer, expected);
+
+        // The requester still owns the request once it has been reported
+        <Test as WitnetConfig>::TimeProvider::set_timestamp(1000);
+        assert_ok!(Witnet::report_result(
+            Origin::signed(5),
+            0,
+            999,
+            [0; 32],
+            vec![0],
+            ResultStatus::Success,
+        ));
+        assert_eq!(Witnet::request_owner(0), Some(requester_id));
+
+        // This should fail because only the owner can transfer the request
+        let transfer = Witnet::transfer_request(new_owner.clone(), 0, new_owner_id);
+        let expected = Err(WitnetError::<Test>::NotRequester.into());
+        assert_eq!(transfer, expected);
+
+        // This should fail because the deposit is no longer reserved in full
+        <Test as WitnetConfig>::Currency::unreserve(&requester_id, 1);
+        let transfer = Witnet::transfer_request(requester.clone(), 0, new_owner_id);
+        let expected = Err(WitnetError::<Test>::DepositNotTransferred.into());
+        assert_eq!(transfer, expected);
+        assert_eq!(Witnet::request_owner(0), Some(requester_id));
+        assert_ok!(<Test as WitnetConfig>::Currency::reserve(&requester_id, 1));
+
+        // Transferring the request also transfers its storage deposit
+        assert_ok!(Witnet::transfer_request(requester.clone(), 0, new_owner_id));
+        System::assert_last_event(
+            WitnetEvent::<Test>::TransferredRequest {
+                request_id: 0,
+                from: requester_id,
+                to: new_owner_id,
+            }
+            .into(),
+        );
+        assert_eq!(Witnet::request_owner(0), Some(new_owner_id));
+        assert_eq!(
+            <Test as WitnetConfig>::Currency::reserved_balance(&requester_id),
+            0
+        );
+        assert_eq!(
+            <Test as WitnetConfig>::Currency::reserved_balance(&new_owner_id),
+            deposit
+        );
+
+        // Only the new owner can prune the request now, and it gets the deposit back
+        let prune = Witnet::prune_request(requester, 0);
+        let expected = Err(WitnetError::<Test>::NotRequester.into());
+        assert_eq!(prune, expected);
+        assert_ok!(Witnet::prune_request(new_owner, 0));
+        assert_eq!(
+            <Test as WitnetConfig>::Currency::reserved_balance(&new_owner_id),
+            0
+        );
+        assert_eq!(
+            <Test as WitnetConfig>::Currency::free_balance(&new_owner_id),
+            1_000 + deposit
+        );
+    });
+}
+
+#[test]
+fn test_result_consumers() {
+    ExtBuilder::default().build_and_execute(|| {
+        let reporter = Origin::signed(5);
+        let requester = Origin::signed(7);
+        let consumer_id = 8;
+
+        <Test as WitnetConfig>::TimeProvider::set_timestamp(500);
+        assert_ok!(post_dummy_request(requester.clone(), None));
+        assert_ok!(post_dummy_request(requester.clone(), None));
+
+        // This should fail because only the requester can choose the consumer
+        let set = Witnet::set_consumer(Origin::signed(consumer_id), 0, Some(consumer_id));
+        let expected = Err(WitnetError::<Test>::NotRequester.into());
+        assert_eq!(set, expected);
+
+        assert_ok!(Witnet::set_consumer(
+            requester.clone(),
+            0,
+            Some(consumer_id)
+        ));
+        System::assert_last_event(
+            WitnetEvent::<Test>::SetConsumer {
+                request_id: 0,
+                consumer: Some(consumer_id),
+            }
+            .into(),
+        );
+
+        // This should fail because the result is not known yet
+        let read = Witnet::read_result(&consumer_id, 0);
+        assert_eq!(read, Err(WitnetError::<Test>::UnresolvedRequest.into()));
+
+        <Test as WitnetConfig>::TimeProvider::set_timestamp(1000);
+        for request_id in 0..2 {
+            assert_ok!(Witnet::report_result(
+                reporter.clone(),
+                request_id,
+                999,
+                [1; 32],
+                vec![24, 42],
+                ResultStatus::Success,
+            ));
+        }
+        let response: Response<Test> = (999, [1; 32], vec![24, 42].try_into().unwrap());
+
+        // This should fail because the consumer cannot be changed once the result is known
+        let set = Witnet::set_consumer(requester, 0, None);
+        let expected = Err(WitnetError::<Test>::AlreadyReported.into());
+        assert_eq!(set, expected);
+
+        // Only the consumer can read the result, which nobody can claim either
+        let read = Witnet::read_result(&7, 0);
+        assert_eq!(read, Err(WitnetError::<Test>::NotConsumer.into()));
+        let claim = Witnet::claim_result(0, &[24, 42]);
+        assert_eq!(claim, Err(WitnetError::<Test>::NotConsumer.into()));
+        assert_eq!(Witnet::read_result(&consumer_id, 0), Ok(response.clone()));
+
+        // Results can only be consumed once
+        assert_eq!(
+            Witnet::consume_result(&consumer_id, 0),
+            Ok(response.clone())
+        );
+        System::assert_last_event(
+            WitnetEvent::<Test>::ConsumedResult {
+                request_id: 0,
+                consumer: consumer_id,
+            }
+            .into(),
+        );
+        let consume = Witnet::consume_result(&consumer_id, 0);
+        assert_eq!(consume, Err(WitnetError::<Test>::ResultConsumed.into()));
+        let read = Witnet::read_result(&consumer_id, 0);
+        assert_eq!(read, Err(WitnetError::<Test>::ResultConsumed.into()));
+
+        // Results without a consumer can be read by anyone, but consumed by nobody
+        assert_eq!(Witnet::read_result(&7, 1), Ok(response));
+        let consume = Witnet::consume_result(&consumer_id, 1);
+        assert_eq!(consume, Err(WitnetError::<Test>::NotConsumer.into()));
+    });
+}
+
+#[test]
+fn test_randomness() {
+    ExtBuilder::default().build_and_execute(|| {
+        let reporter = Origin::signed(5);
+        let requester = Origin::signed(7);
+        let random_bytes = [7; 32];
+        let result_bytes = [&[0x58, 0x20][..], &random_bytes[..]].concat();
+        let no_randomness = (BlakeTwo256::hash(b"subject"), 0);
+
+        // Randomness requests are made of the standard Witnet RNG request
+        <Test as WitnetConfig>::TimeProvider::set_timestamp(500);
+        assert_ok!(Witnet::request_randomness(requester.clone(), 10));
+        assert_eq!(
+            Witnet::request_bytes(0),
+            Some(randomness::RANDOMNESS_REQUEST.to_vec())
+        );
+        assert_eq!(
+            <Test as WitnetConfig>::Currency::reserved_balance(&7),
+            10 + request_deposit::<Test>(randomness::RANDOMNESS_REQUEST.len())
+        );
+
+        // There is no randomness until the first randomness request gets resolved
+        assert_eq!(Witnet::random(b"subject"), no_randomness);
+
+        // Error results do not count as randomness
+        <Test as WitnetConfig>::TimeProvider::set_timestamp(1000);
+        assert_ok!(Witnet::report_result(
+            reporter.clone(),
+            0,
+            999,
+            [0; 32],
+            vec![0xd8, 0x27, 0x81, 0x00],
+            ResultStatus::Error(0),
+        ));
+        run_to_block(1 + RANDOMNESS_DELAY);
+        assert_eq!(Witnet::random(b"subject"), no_randomness);
+
+        // Nor do results of other requests, even if they look like randomness
+        assert_ok!(Witnet::request_randomness(requester.clone(), 10));
+        assert_ok!(Witnet::post_request(
+            requester.clone(),
+            10,
+            randomness::RANDOMNESS_REQUEST.to_vec()
+        ));
+        <Test as WitnetConfig>::TimeProvider::set_timestamp(2000);
+        assert_ok!(Witnet::report_result(
+            reporter.clone(),
+            2,
+            1999,
+            [2; 32],
+            result_bytes.clone(),
+            ResultStatus::Success,
+        ));
+        run_to_block(1 + 2 * RANDOMNESS_DELAY);
+        assert_eq!(Witnet::random(b"subject"), no_randomness);
+
+        // The randomness is only handed out once it is old enough, mixed with the parent hash
+        let reported_at = System::block_number();
+        assert_ok!(Witnet::report_result(
+            reporter.clone(),
+            1,
+            1999,
+            [1; 32],
+            result_bytes,
+            ResultStatus::Success,
+        ));
+        run_to_block(reported_at + RANDOMNESS_DELAY - 1);
+        assert_eq!(Witnet::random(b"subject"), no_randomness);
+        run_to_block(reported_at + RANDOMNESS_DELAY);
+        System::assert_last_event(WitnetEvent::<Test>::UpdatedRandomness { request_id: 1 }.into());
+        let mixed = sp_io::hashing::blake2_256(&(random_bytes, System::parent_hash()).encode());
+        let expected = BlakeTwo256::hash_of(&(mixed, &b"subject"[..]));
+        assert_eq!(
+            Witnet::random(b"subject"),
+            (expected, reported_at + RANDOMNESS_DELAY)
+        );
+
+        // Different subjects get different random values out of the same randomness
+        let (other, _) = Witnet::random(b"other subject");
+        assert_ne!(other, expected);
+
+        // Randomness that is reserved to a consumer stays out of the randomness handed out
+        assert_ok!(Witnet::request_randomness(requester.clone(), 10));
+        assert_ok!(Witnet::set_consumer(requester, 3, Some(8)));
+        <Test as WitnetConfig>::TimeProvider::set_timestamp(3000);
+        assert_ok!(Witnet::report_result(
+            reporter,
+            3,
+            2999,
+            [3; 32],
+            [&[0x58, 0x20][..], &[8; 32][..]].concat(),
+            ResultStatus::Success,
+        ));
+        run_to_block(System::block_number() + RANDOMNESS_DELAY);
+        assert_eq!(
+            Witnet::random(b"subject"),
+            (expected, reported_at + RANDOMNESS_DELAY)
+        );
+    });
+}
+
+#[test]
+fn test_unregister_template() {
+    ExtBuilder::default().build_and_execute(|| {
+        let reward = 10;
+        let owner_id = 7;
+        let owner = Origin::signed(owner_id);
+        let requester = Origin::signed(8);
+        let bytes = witnet_request(b"https://api.example.com");
+        let template_id = sp_io::hashing::blake2_256(&bytes);
+
+        <Test as WitnetConfig>::TimeProvider::set_timestamp(500);
+        assert_ok!(Witnet::register_template(owner.clone(), bytes));
+        assert_ok!(Witnet::post_request_from_template(
+            requester.clone(),
+            template_id,
+            reward,
+            Default::default()
+        ));
+
+        // This should fail because only the owner can unregister the template
+        let unregister = Witnet::unregister_template(requester.clone(), template_id);
+        let expected = Err(WitnetError::<Test>::NotTemplateOwner.into());
+        assert_eq!(unregister, expected);
+
+        // This should fail because a request posted from the template is still pending
+        let unregister = Witnet::unregister_template(owner.clone(), template_id);
+        let expected = Err(WitnetError::<Test>::TemplateInUse.into());
+        assert_eq!(unregister, expected);
+
+        // Once the request is resolved, the template can go away along with its deposit
+        <Test as WitnetConfig>::TimeProvider::set_timestamp(1000);
+        assert_ok!(report_result(
+            Origin::signed(5),
+            0,
+            999,
+            [0; 32],
+            vec![0],
+            ResultStatus::Success
+        ));
+        assert_ok!(Witnet::unregister_template(owner, template_id));
+        System::assert_last_event(WitnetEvent::<Test>::UnregisteredTemplate { template_id }.into());
+        assert_eq!(
+            <Test as WitnetConfig>::Currency::reserved_balance(&owner_id),
+            0
+        );
+
+        // This should fail because the template no longer exists
+        let post =
+            Witnet::post_request_from_template(requester, template_id, reward, Default::default());
+        let expected = Err(WitnetError::<Test>::UnknownTemplate.into());
+        assert_eq!(post, expected);
+    });
+}
+
+#[test]
+fn test_send_request_params() {
+    let bytes = witnet_request(b"https://api.example.com");
+    let params = crate::witnet::send_request_params(&bytes, 1).unwrap();
+    let expected = concat!(
+        r#"{"dro":{"data_request":{"time_lock":0,"retrieve":[{"kind":"HTTP-GET","#,
+        r#""url":"https://api.example.com","script":[128],"body":[],"headers":[]}],"#,
+        r#""aggregate":{"filters":[],"reducer":0},"tally":{"filters":[],"reducer":0}},"#,
+        r#""witness_reward":1000,"witnesses":0,"commit_and_reveal_fee":0,"#,
+        r#""min_consensus_percentage":0,"collateral":0},"fee":1}"#,
+    );
+    assert_eq!(std::str::from_utf8(&params), Ok(expected));
+
+    // This should fail because the bytes are not a valid `DataRequestOutput`
+    assert_eq!(crate::witnet::send_request_params(&[1, 2, 3], 1), Err(()));
+}
+
+#[test]
+fn test_result_status() {
+    use crate::witnet::result_status;
+
+    assert_eq!(result_status(&[0x18, 0x2a]), ResultStatus::Success);
+    // RADON errors, be their codes tiny or one byte integers
+    assert_eq!(
+        result_status(&[0xd8, 0x27, 0x81, 0x12]),
+        ResultStatus::Error(0x12)
+    );
+    assert_eq!(
+        result_status(&[0xd8, 0x27, 0x82, 0x18, 0x51, 0x00]),
+        ResultStatus::Error(0x51)
+    );
+    assert_eq!(result_status(&[0xd8, 0x27]), ResultStatus::Error(0));
+}
+
+#[test]
+fn test_migrate_to_v4() {
+    ExtBuilder::default().build_and_execute(|| {
+        let requester_id = 7;
+        let put_request = |request_id: RequestId, entry: RequestEntry<Test>| {
+            let key = Twox64Concat::hash(&request_id.encode());
+            frame_support::migration::put_storage_value(b"Witnet", b"Requests", &key, entry);
+        };
+
+        // Post and resolve a request, which has a storage deposit
+        <Test as WitnetConfig>::TimeProvider::set_timestamp(500);
+        assert_ok!(post_dummy_request(Origin::signed(requester_id), None));
+        <Test as WitnetConfig>::TimeProvider::set_timestamp(1000);
+        assert_ok!(report_result(
+            Origin::signed(5),
+            0,
+            999,
+            [0; 32],
+            vec![0],
+            ResultStatus::Success
+        ));
+
+        // Make it look as if owners were cleared on report, and as if there were a result of a
+        // request that was posted before there were storage deposits
+        StorageVersion::new(3).put::<Witnet>();
+        let (query, response, _) = crate::Requests::<Test>::get(0).unwrap();
+        put_request(0, (query, response.clone(), None));
+        put_request(1, (None, response, None));
+        assert_eq!(Witnet::request_owner(0), None);
+
+        Witnet::on_runtime_upgrade();
+        run_to_block(3);
+        assert_eq!(Witnet::on_chain_storage_version(), StorageVersion::new(4));
+
+        // The owner comes back from the storage deposit, if there is one
+        assert_eq!(Witnet::request_owner(0), Some(requester_id));
+        assert_eq!(Witnet::request_owner(1), None);
+
+        // This should fail because nobody owns the result
+        let transfer = Witnet::transfer_request(Origin::signed(requester_id), 1, 8);
+        let expected = Err(WitnetError::<Test>::NotRequester.into());
+        assert_eq!(transfer, expected);
+    });
+}
+
+#[test]
+fn test_reserved_results_stay_out_of_feeds() {
+    ExtBuilder::default().build_and_execute(|| {
+        let reporter = Origin::signed(5);
+        let requester = Origin::signed(7);
+        let sibling = Origin::signed(SIBLING_ACCOUNT_OFFSET + u64::from(SIBLING_PARA_ID));
+        let consumer_id = 8;
+        let bytes = vec![1, 2, 3];
+        let feed_id = feed_id_for(&bytes);
+
+        <Test as WitnetConfig>::TimeProvider::set_timestamp(500);
+        assert_ok!(Witnet::post_feed_request(
+            requester.clone(),
+            0,
+            bytes.clone()
+        ));
+        assert_ok!(Witnet::post_request(requester.clone(), 0, bytes));
+        assert_ok!(Witnet::post_remote_request(sibling.clone(), 123, vec![]));
+
+        // These should fail because the results go to a feed and back to another chain
+        let set = Witnet::set_consumer(requester.clone(), 0, Some(consumer_id));
+        let expected = Err(WitnetError::<Test>::UnreservableResult.into());
+        assert_eq!(set, expected);
+        let set = Witnet::set_consumer(sibling, 2, Some(consumer_id));
+        assert_eq!(set, expected);
+
+        // Requests with the same bytes that are not part of the feed can be reserved, and their
+        // results never reach it
+        assert_ok!(Witnet::set_consumer(requester, 1, Some(consumer_id)));
+        <Test as WitnetConfig>::TimeProvider::set_timestamp(1000);
+        assert_ok!(report_result(
+            reporter.clone(),
+            1,
+            999,
+            [1; 32],
+            vec![1],
+            ResultStatus::Success
+        ));
+        assert_eq!(Witnet::latest_value(&feed_id), None);
+        let response: Response<Test> = (999, [1; 32], vec![1].try_into().unwrap());
+        assert_eq!(Witnet::read_result(&consumer_id, 1), Ok(response));
+
+        // The result of the feed request is still there for anyone to read
+        assert_ok!(report_result(
+            reporter,
+            0,
+            998,
+            [0; 32],
+            vec![0],
+            ResultStatus::Success
+        ));
+        let expected = (0, (998, [0; 32], vec![0].try_into().unwrap()));
+        assert_eq!(Witnet::latest_value(&feed_id), Some(expected));
+    });
+}
+
+#[cfg(feature = "ocw")]
+#[test]
+fn test_offchain_relayer_assignments() {
+    use crate::ocw::DEFAULT_NODE_URL;
+    use sp_core::offchain::{
+        testing::{PendingRequest, TestOffchainExt, TestTransactionPoolExt},
+        OffchainDbExt, OffchainWorkerExt, TransactionPoolExt,
+    };
+
+    let (offchain, offchain_state) = TestOffchainExt::new();
+    let (pool, _) = TestTransactionPoolExt::new();
+    let mut ext = ExtBuilder::default().build();
+    ext.register_extension(OffchainDbExt::new(offchain.clone()));
+    ext.register_extension(OffchainWorkerExt::new(offchain));
+    ext.register_extension(TransactionPoolExt::new(pool));
+    // The keystore holds the key of account #5, which is an operator
+    UintAuthorityId::set_all_keys(vec![5]);
+
+    ext.execute_with(|| {
+        System::set_block_number(1);
+        <Test as WitnetConfig>::TimeProvider::set_timestamp(500);
+        assert_ok!(Witnet::add_operator(Origin::signed(5), 8));
+        let bytes = witnet_request(b"https://api.example.com");
+        assert_ok!(Witnet::post_request(Origin::signed(7), 10, bytes));
+        crate::Assignments::<Test>::insert(0, (8, 3));
+
+        // The request is not sent to the Witnet node while it is assigned to another operator,
+        // which is checked by the test failing on any call that is not expected
+        Witnet::offchain_worker(1);
+        System::set_block_number(2);
+        Witnet::offchain_worker(2);
+
+        // Once the exclusivity window is over, anyone can relay it
+        System::set_block_number(3);
+        offchain_state.write().expect_request(PendingRequest {
+            method: "POST".into(),
+            uri: DEFAULT_NODE_URL.into(),
+            headers: vec![("Content-Type".into(), "application/json".into())],
+            body: SEND_REQUEST_CALL.as_bytes().to_vec(),
+            response: Some(
+                format!(
+                    r#"{{"jsonrpc":"2.0","id":1,"result":"{}"}}"#,
+                    "ab".repeat(32)
+                )
+                .into_bytes(),
+            ),
+            sent: true,
+            ..Default::default()
+        });
+        Witnet::offchain_worker(3);
+    });
+}
+
+#[test]
+fn test_declared_weights() {
+    ExtBuilder::default().build_and_execute(|| {
+        // Calls that are not refunded are charged for the bytes that they actually take
+        let bytes = witnet_request(b"https://api.example.com");
+        let len = bytes.len();
+        let feed_call = crate::Call::<Test>::post_feed_request {
+            reward: 10,
+            bytes: bytes.clone(),
+        };
+        assert_eq!(
+            feed_call.get_dispatch_info().weight,
+            post_feed_request_weight::<Test>(len)
+        );
+        let register_call = crate::Call::<Test>::register_template { bytes };
+        assert_eq!(
+            register_call.get_dispatch_info().weight,
+            register_template_weight::<Test>(len)
+        );
+        assert!(register_template_weight::<Test>(len) < register_template_weight::<Test>(len + 1));
+
+        // And requests from templates for the number and the size of their arguments
+        let args = template_args(vec![b"BTC".to_vec(), b"USD".to_vec()]);
+        let template_call = crate::Call::<Test>::post_request_from_template {
+            template_id: [0; 32],
+            reward: 10,
+            args: args.clone(),
+        };
+        assert_eq!(
+            template_call.get_dispatch_info().weight,
+            post_request_from_template_weight::<Test>(&args)
+        );
+        let fewer_args = template_args(vec![b"BTCUSD".to_vec()]);
+        assert!(
+            post_request_from_template_weight::<Test>(&fewer_args)
+                < post_request_from_template_weight::<Test>(&args)
+        );
+    });
+}
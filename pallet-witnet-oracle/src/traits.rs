@@ -1,10 +1,19 @@
 use std::convert::TryInto;
 
 use frame_support::{
-    sp_runtime::traits::Zero,
-    traits::{tokens::BalanceStatus, Time},
+    dispatch::DispatchErrorWithPostInfo,
+    sp_io::hashing::blake2_256,
+    sp_runtime::traits::{Hash, One, Saturating, Zero},
+    traits::{tokens::BalanceStatus, Randomness, Time},
 };
 use frame_system::ensure_signed;
+use xcm::latest::{
+    Instruction::{BuyExecution, Transact, WithdrawAsset},
+    OriginKind,
+    WeightLimit::Unlimited,
+    Xcm,
+};
+use xcm_executor::traits::Convert;
 
 use core::convert::Into;
 
@@ -12,20 +21,67 @@ use crate::prelude::*;
 
 use super::*;
 
+/// How many consecutive blocks to try when looking for room in the expiry queue, before falling
+/// back to lazy expiry.
+pub(crate) const MAX_EXPIRY_SCHEDULING_ATTEMPTS: u32 = 10;
+
 pub trait WitnetOracle<T, O>
 where
     T: Config,
 {
     fn post_request(origin: O, reward: BalanceFor<T>, bytes: Vec<u8>) -> DispatchResult;
+    fn post_request_with_timeout(
+        origin: O,
+        reward: BalanceFor<T>,
+        bytes: Vec<u8>,
+        timeout: T::BlockNumber,
+    ) -> DispatchResult;
+    fn post_feed_request(origin: O, reward: BalanceFor<T>, bytes: Vec<u8>) -> DispatchResult;
     fn report_result(
         origin: O,
         request_id: u64,
         timestamp: TimestampFor<T>,
         dr_tx_hash: [u8; 32],
         result_bytes: Vec<u8>,
+        status: ResultStatus,
     ) -> DispatchResult;
     fn add_operator(origin: O, account_id: T::AccountId) -> DispatchResult;
     fn remove_operator(origin: O, account_id: T::AccountId) -> DispatchResult;
+    fn subscribe(
+        origin: O,
+        reward: BalanceFor<T>,
+        budget: BalanceFor<T>,
+        period: T::BlockNumber,
+        bytes: Vec<u8>,
+    ) -> DispatchResult;
+    fn cancel_subscription(origin: O, subscription_id: SubscriptionId) -> DispatchResult;
+    fn prune_request(origin: O, request_id: RequestId) -> DispatchResult;
+    fn set_operator_info(
+        origin: O,
+        name: Vec<u8>,
+        witnet_pkh: [u8; 20],
+        url: Vec<u8>,
+    ) -> DispatchResult;
+    fn register_template(origin: O, bytes: Vec<u8>) -> DispatchResult;
+    fn unregister_template(origin: O, template_id: TemplateId) -> DispatchResult;
+    fn post_request_from_template(
+        origin: O,
+        template_id: TemplateId,
+        reward: BalanceFor<T>,
+        args: TemplateArgs<T>,
+    ) -> DispatchResult;
+    fn set_operator_key(origin: O, key: T::OperatorKey) -> DispatchResult;
+    fn transfer_request(
+        origin: O,
+        request_id: RequestId,
+        new_owner: T::AccountId,
+    ) -> DispatchResult;
+    fn set_consumer(
+        origin: O,
+        request_id: RequestId,
+        consumer: Option<T::AccountId>,
+    ) -> DispatchResult;
+    fn request_randomness(origin: O, reward: BalanceFor<T>) -> DispatchResult;
 }
 
 impl<T, O> WitnetOracle<T, O> for Pallet<T>
@@ -40,28 +96,43 @@ where
         // Ensure that the transaction is signed, and get hold of signer data
         let sender = ensure_signed(origin)?;
 
-        // Reject oversized requests
-        let bytes: BoundedVec<_, T::MaxByteSize> = bytes
-            .try_into()
-            .map_err(|()| Error::<T>::OversizedRequest)?;
+        reserve_and_post_request::<T>(sender, reward, bytes, T::DefaultRequestTimeout::get())?;
 
-        // Check that the report reward foreseeably covers cost of reporting
-        let required_reward = estimate_report_reward::<BalanceFor<T>>(bytes.len());
-        ensure!(reward >= required_reward, Error::<T>::UnderpayingRequest);
+        Ok(())
+    }
+
+    fn post_request_with_timeout(
+        origin: O,
+        reward: BalanceFor<T>,
+        bytes: Vec<u8>,
+        timeout: T::BlockNumber,
+    ) -> DispatchResult
+    where
+        O: Into<Result<frame_system::RawOrigin<T::AccountId>, O>>,
+    {
+        // Ensure that the transaction is signed, and get hold of signer data
+        let sender = ensure_signed(origin)?;
 
-        // Try to put aside the reward to be paid later to the reporter of the result
-        T::Currency::reserve(&sender, reward)?;
+        // A timeout of zero would make the request expire right away
+        ensure!(!timeout.is_zero(), Error::<T>::InvalidTimeout);
 
-        // Use next request ID
-        let request_id = NextRequestId::<T>::get();
+        reserve_and_post_request::<T>(sender, reward, bytes, timeout)?;
+
+        Ok(())
+    }
 
-        // Store request and deposit event to signal readiness for fulfillment
-        let request_entry: RequestEntry<T> = (Some((bytes, reward)), None, Some(sender.clone()));
-        Requests::<T>::insert(request_id, request_entry);
-        Self::deposit_event(Event::<T>::PostedRequest { request_id, requester: sender });
+    fn post_feed_request(origin: O, reward: BalanceFor<T>, bytes: Vec<u8>) -> DispatchResult
+    where
+        O: Into<Result<frame_system::RawOrigin<T::AccountId>, O>>,
+    {
+        // Ensure that the transaction is signed, and get hold of signer data
+        let sender = ensure_signed(origin)?;
 
-        // Increase next request ID
-        NextRequestId::<T>::put(request_id.wrapping_add(1));
+        // Requests with the very same bytes share the same feed
+        let feed_id = feed_id_for(&bytes);
+        let timeout = T::DefaultRequestTimeout::get();
+        let request_id = reserve_and_post_request::<T>(sender, reward, bytes, timeout)?;
+        RequestFeeds::<T>::insert(request_id, feed_id);
 
         Ok(())
     }
@@ -72,6 +143,7 @@ where
         timestamp: TimestampFor<T>,
         dr_tx_hash: [u8; 32],
         result_bytes: Vec<u8>,
+        status: ResultStatus,
     ) -> DispatchResult
     where
         O: Into<Result<frame_system::RawOrigin<T::AccountId>, O>>,
@@ -79,39 +151,14 @@ where
         // Ensure that the transaction is signed, and get hold of signer data
         let reporter = ensure_signed(origin)?;
 
-        // Ensure that the sender is entitled to report
-        ensure!(
-            Operators::<T>::contains_key(&reporter),
-            Error::<T>::UnauthorizedOperator
-        );
-
-        // Ensure that timestamp is older than current block
-        let now = T::TimeProvider::now();
-        ensure!(timestamp < now, Error::<T>::ResultFromFuture);
-
-        // Ensure that the CBOR bytes are not empty
-        ensure!(!result_bytes.is_empty(), Error::<T>::EmptyResult);
-
-        // Reject oversized results
-        let bounded_bytes: BoundedVec<_, T::MaxByteSize> = result_bytes
-            .try_into()
-            .map_err(|()| Error::<T>::OversizedResult)?;
-
-        // Do storage related operations in a separate `inner_report_result` function
-        // This will allow reusing part that logic in a future batch reporting method
-        let (reward, requester) =
-            inner_report_result::<T>(request_id, timestamp, dr_tx_hash, bounded_bytes, true)?;
-        if reward > Zero::zero() {
-            // Transfer reserved values from the requester to the reporter
-            T::Currency::repatriate_reserved(&requester, &reporter, reward, BalanceStatus::Free)?;
-            // Send reward to the reporter
-            T::Currency::unreserve(&reporter, reward);
-        }
-
-        // Deposit event to signal eventual resolution of the data request
-        Self::deposit_event(Event::<T>::PostedResult { request_id, reporter });
-
-        Ok(())
+        report_result_as::<T>(
+            reporter,
+            request_id,
+            timestamp,
+            dr_tx_hash,
+            result_bytes,
+            status,
+        )
     }
 
     fn add_operator(origin: O, account_id: T::AccountId) -> DispatchResult
@@ -124,8 +171,16 @@ where
         // Ensure that the operator is not trying to add themself
         ensure!(account_id != sender, Error::<T>::OperatorSelfAddition);
 
+        // Ensure that there is room for one more operator
+        ensure!(
+            Operators::<T>::contains_key(&account_id)
+                || Operators::<T>::count() < T::MaxOperators::get(),
+            Error::<T>::TooManyOperators
+        );
+
         // Insert the account id of the new operator into the operators collection
         Operators::<T>::insert(account_id.clone(), ());
+        note_operator_joined::<T>(&account_id);
 
         // Deposit event to signal addition of the new operator
         Self::deposit_event(Event::<T>::AddedOperator {
@@ -146,8 +201,16 @@ where
         // Ensure that the operator is not trying to remove themself
         ensure!(account_id != sender, Error::<T>::OperatorSelfRemoval);
 
-        // Try to remove the account id from the operators collection
-        Operators::<T>::take(account_id.clone()).ok_or(Error::<T>::UnknownOperator)?;
+        // Ensure that enough operators remain after the removal
+        ensure!(
+            Operators::<T>::contains_key(&account_id),
+            Error::<T>::UnknownOperator
+        );
+        ensure!(can_remove_operator::<T>(), Error::<T>::TooFewOperators);
+
+        // Remove the account id from the operators collection
+        Operators::<T>::remove(&account_id);
+        forget_operator::<T>(&account_id);
 
         Self::deposit_event(Event::<T>::RemovedOperator {
             removed_operator: account_id,
@@ -156,62 +219,1525 @@ where
 
         Ok(())
     }
-}
 
-fn ensure_operator<T, O>(origin: O) -> Result<T::AccountId, DispatchError>
-where
-    T: Config,
-    O: Into<Result<frame_system::RawOrigin<T::AccountId>, O>>,
-{
-    // Ensure that the transaction is signed, and get hold of signer data
-    let sender = ensure_signed(origin)?;
+    fn subscribe(
+        origin: O,
+        reward: BalanceFor<T>,
+        budget: BalanceFor<T>,
+        period: T::BlockNumber,
+        bytes: Vec<u8>,
+    ) -> DispatchResult
+    where
+        O: Into<Result<frame_system::RawOrigin<T::AccountId>, O>>,
+    {
+        // Ensure that the transaction is signed, and get hold of signer data
+        let owner = ensure_signed(origin)?;
 
-    // Ensure that the sender is entitled to remove an operator
-    ensure!(
-        Operators::<T>::contains_key(&sender),
-        Error::<T>::UnauthorizedOperator
-    );
+        // Ensure that posting has not been paused
+        ensure!(!PausedOperations::<T>::get().posting, Error::<T>::Paused);
 
-    Ok(sender)
-}
+        // A subscription with a period of zero would never be triggered again
+        ensure!(!period.is_zero(), Error::<T>::InvalidSubscriptionPeriod);
 
-fn inner_report_result<T: Config>(
-    request_id: u64,
-    timestamp: TimestampFor<T>,
-    dr_tx_hash: [u8; 32],
-    result_bytes: BoundedVec<u8, T::MaxByteSize>,
-    drop: bool,
-) -> Result<(BalanceFor<T>, T::AccountId), Error<T>> {
-    // Retrieve request info from storage, fail if unknown
-    <Requests<T>>::try_mutate(request_id, |entry| {
-        match entry {
-            // Ensure the request exists
+        // Reject oversized requests
+        let bytes: BoundedVec<_, T::MaxRequestSize> = bytes
+            .try_into()
+            .map_err(|()| Error::<T>::OversizedRequest)?;
+
+        // Check that the report reward foreseeably covers cost of reporting
+        let required_reward = estimate_report_reward::<BalanceFor<T>>(bytes.len());
+        ensure!(reward >= required_reward, Error::<T>::UnderpayingRequest);
+
+        // A reward of zero would let the subscription post requests forever at no cost
+        ensure!(!reward.is_zero(), Error::<T>::UnderpayingRequest);
+
+        // Check that the budget covers at least the first request
+        ensure!(budget >= reward, Error::<T>::InsufficientSubscriptionBudget);
+
+        // Try to put aside the whole budget, which will be spent request by request
+        T::Currency::reserve(&owner, budget)?;
+
+        // Use next subscription ID, and schedule the first request for the next block
+        let subscription_id = NextSubscriptionId::<T>::get();
+        let next_block = frame_system::Pallet::<T>::block_number().saturating_add(One::one());
+        if let Err(error) = schedule_subscription::<T>(subscription_id, next_block) {
+            T::Currency::unreserve(&owner, budget);
+            return Err(error.into());
+        }
+
+        // Store subscription and deposit event to signal its creation
+        let subscription = Subscription {
+            owner: owner.clone(),
+            bytes,
+            reward,
+            budget,
+            period,
+        };
+        Subscriptions::<T>::insert(subscription_id, subscription);
+        Self::deposit_event(Event::<T>::Subscribed {
+            subscription_id,
+            owner,
+        });
+
+        // Increase next subscription ID
+        NextSubscriptionId::<T>::put(subscription_id.wrapping_add(1));
+
+        Ok(())
+    }
+
+    fn cancel_subscription(origin: O, subscription_id: SubscriptionId) -> DispatchResult
+    where
+        O: Into<Result<frame_system::RawOrigin<T::AccountId>, O>>,
+    {
+        // Ensure that the transaction is signed, and get hold of signer data
+        let sender = ensure_signed(origin)?;
+
+        // Ensure that the subscription exists and belongs to the sender
+        let subscription =
+            Subscriptions::<T>::get(subscription_id).ok_or(Error::<T>::UnknownSubscription)?;
+        ensure!(
+            subscription.owner == sender,
+            Error::<T>::NotSubscriptionOwner
+        );
+
+        // Removing the subscription is enough, as the queue skips unknown subscriptions
+        Subscriptions::<T>::remove(subscription_id);
+
+        // Give back whatever is left of the budget
+        T::Currency::unreserve(&sender, subscription.budget);
+
+        Self::deposit_event(Event::<T>::CancelledSubscription { subscription_id });
+
+        Ok(())
+    }
+
+    fn prune_request(origin: O, request_id: RequestId) -> DispatchResult
+    where
+        O: Into<Result<frame_system::RawOrigin<T::AccountId>, O>>,
+    {
+        // Ensure that the transaction is signed, and get hold of signer data
+        let sender = ensure_signed(origin)?;
+
+        // Ensure that the request is no longer waiting for a result
+        match Requests::<T>::get(request_id) {
             None => Err(Error::<T>::UnknownRequest),
-            // Ensure the request had not been already reported
-            Some((_, Some(_), ..)) => Err(Error::<T>::AlreadyReported),
-            // If the query is still there, we can operate, otherwise do nothing
-            Some((query_option @ Some(_), report_option @ None, requester_option @ Some(_))) => {
-                // It is safe to unwrap the query reward here because it's guarded above
-                let reward = query_option.clone().unwrap().1;
-                let requester = requester_option.clone().unwrap();
-                // If drop is set to true, remove query and requesterwhen inserting the report
-                if drop {
-                    *query_option = None;
-                    *requester_option = None;
-                }
-                // Insert the report
-                *report_option = Some((timestamp, dr_tx_hash, result_bytes));
+            Some((Some(_), None, _)) => Err(Error::<T>::RequestStillPending),
+            Some(_) => Ok(()),
+        }?;
 
-                Ok((reward, requester))
+        // Ensure that the sender is the one who paid the storage deposit
+        let (depositor, deposit) =
+            Deposits::<T>::get(request_id).ok_or(Error::<T>::UnknownRequest)?;
+        ensure!(depositor == sender, Error::<T>::NotRequester);
+
+        // Free the storage, and give the deposit back
+        Requests::<T>::remove(request_id);
+        Deposits::<T>::remove(request_id);
+        ResultErrors::<T>::remove(request_id);
+        ResultHashes::<T>::remove(request_id);
+        RequestTemplates::<T>::remove(request_id);
+        Consumers::<T>::remove(request_id);
+        ConsumedResults::<T>::remove(request_id);
+        T::Currency::unreserve(&depositor, deposit);
+
+        Self::deposit_event(Event::<T>::PrunedRequest { request_id });
+
+        Ok(())
+    }
+
+    fn set_operator_info(
+        origin: O,
+        name: Vec<u8>,
+        witnet_pkh: [u8; 20],
+        url: Vec<u8>,
+    ) -> DispatchResult
+    where
+        O: Into<Result<frame_system::RawOrigin<T::AccountId>, O>>,
+    {
+        // Ensure that the sender is an operator, and get hold of its account id
+        let operator = ensure_operator::<T, O>(origin)?;
+
+        // Reject oversized names and URLs
+        let name: BoundedVec<_, T::MaxOperatorInfoLength> = name
+            .try_into()
+            .map_err(|()| Error::<T>::OversizedOperatorInfo)?;
+        let url: BoundedVec<_, T::MaxOperatorInfoLength> = url
+            .try_into()
+            .map_err(|()| Error::<T>::OversizedOperatorInfo)?;
+
+        // Operators that joined before their details were tracked are considered to join now
+        OperatorDetails::<T>::mutate(&operator, |details| {
+            let joined_at = details
+                .as_ref()
+                .map(|details| details.joined_at)
+                .unwrap_or_else(frame_system::Pallet::<T>::block_number);
+            *details = Some(OperatorInfo {
+                name,
+                witnet_pkh,
+                url,
+                joined_at,
+            });
+        });
+
+        Self::deposit_event(Event::<T>::UpdatedOperatorInfo { operator });
+
+        Ok(())
+    }
+
+    fn set_operator_key(origin: O, key: T::OperatorKey) -> DispatchResult
+    where
+        O: Into<Result<frame_system::RawOrigin<T::AccountId>, O>>,
+    {
+        // Ensure that the sender is an operator, and get hold of its account id
+        let operator = ensure_operator::<T, O>(origin)?;
+
+        OperatorKeys::<T>::insert(&operator, key);
+
+        Self::deposit_event(Event::<T>::UpdatedOperatorKey { operator });
+
+        Ok(())
+    }
+
+    fn register_template(origin: O, bytes: Vec<u8>) -> DispatchResult
+    where
+        O: Into<Result<frame_system::RawOrigin<T::AccountId>, O>>,
+    {
+        // Ensure that the transaction is signed, and get hold of signer data
+        let owner = ensure_signed(origin)?;
+
+        // Reject oversized templates
+        let bytes: BoundedVec<_, T::MaxRequestSize> = bytes
+            .try_into()
+            .map_err(|()| Error::<T>::OversizedRequest)?;
+
+        // Ensure that the template is a Witnet data request, as every request posted from it would
+        // fail otherwise
+        protobuf::check_data_request_output(&bytes).map_err(|()| Error::<T>::InvalidTemplate)?;
+
+        // Templates are identified by the hash of their bytes, so they can only be registered once
+        let template_id = blake2_256(&bytes);
+        ensure!(
+            !Templates::<T>::contains_key(template_id),
+            Error::<T>::TemplateAlreadyRegistered
+        );
+
+        // Try to put aside the storage deposit, which is the same as for a request of the same size
+        let deposit = request_deposit::<T>(bytes.len());
+        T::Currency::reserve(&owner, deposit)?;
+
+        Templates::<T>::insert(template_id, (owner.clone(), bytes, deposit));
+        Self::deposit_event(Event::<T>::RegisteredTemplate { template_id, owner });
+
+        Ok(())
+    }
+
+    fn unregister_template(origin: O, template_id: TemplateId) -> DispatchResult
+    where
+        O: Into<Result<frame_system::RawOrigin<T::AccountId>, O>>,
+    {
+        // Ensure that the transaction is signed, and get hold of signer data
+        let sender = ensure_signed(origin)?;
+
+        // Ensure that the template exists, and that it belongs to the sender
+        let (owner, _, deposit) =
+            Templates::<T>::get(template_id).ok_or(Error::<T>::UnknownTemplate)?;
+        ensure!(owner == sender, Error::<T>::NotTemplateOwner);
+
+        // Ensure that relayers no longer need the bytes of the template for any pending request
+        ensure!(
+            !PendingTemplateRequests::<T>::contains_key(template_id),
+            Error::<T>::TemplateInUse
+        );
+
+        // Free the storage, and give the deposit back
+        Templates::<T>::remove(template_id);
+        T::Currency::unreserve(&owner, deposit);
+        Self::deposit_event(Event::<T>::UnregisteredTemplate { template_id });
+
+        Ok(())
+    }
+
+    fn post_request_from_template(
+        origin: O,
+        template_id: TemplateId,
+        reward: BalanceFor<T>,
+        args: TemplateArgs<T>,
+    ) -> DispatchResult
+    where
+        O: Into<Result<frame_system::RawOrigin<T::AccountId>, O>>,
+    {
+        // Ensure that the transaction is signed, and get hold of signer data
+        let sender = ensure_signed(origin)?;
+
+        // Ensure that the template exists
+        let (_, bytes, _) = Templates::<T>::get(template_id).ok_or(Error::<T>::UnknownTemplate)?;
+
+        // Ensure that the arguments fill all the placeholders, if any, without making the request
+        // oversized
+        let args: Vec<&[u8]> = args.iter().map(|arg| arg.as_slice()).collect();
+        let filled = templates::fill_template(&bytes, &args, T::MaxRequestSize::get() as usize)
+            .map_err(|error| match error {
+                templates::FillError::Invalid => Error::<T>::InvalidTemplateArguments,
+                templates::FillError::Oversized => Error::<T>::OversizedRequest,
+            })?;
+
+        // Requests with arguments are different from the template, so they are posted in full
+        if !args.is_empty() {
+            let timeout = T::DefaultRequestTimeout::get();
+            reserve_and_post_request::<T>(sender, reward, filled, timeout)?;
+
+            return Ok(());
+        }
+
+        // Check that the report reward foreseeably covers cost of reporting
+        let required_reward = estimate_report_reward::<BalanceFor<T>>(bytes.len());
+        ensure!(reward >= required_reward, Error::<T>::UnderpayingRequest);
+
+        // The request only references the template, rather than storing a copy of its bytes
+        let timeout = T::DefaultRequestTimeout::get();
+        let request_id =
+            reserve_and_post_bounded_request::<T>(sender, reward, Default::default(), timeout)?;
+        RequestTemplates::<T>::insert(request_id, template_id);
+        PendingTemplateRequests::<T>::mutate(template_id, |count| *count = count.saturating_add(1));
+
+        Ok(())
+    }
+
+    fn transfer_request(origin: O, request_id: RequestId, new_owner: T::AccountId) -> DispatchResult
+    where
+        O: Into<Result<frame_system::RawOrigin<T::AccountId>, O>>,
+    {
+        // Ensure that the transaction is signed, and get hold of signer data
+        let sender = ensure_signed(origin)?;
+
+        // Ensure that the request is no longer waiting for a result, as its reward is still
+        // reserved from the requester, and that it belongs to the sender
+        match Requests::<T>::get(request_id) {
+            None => Err(Error::<T>::UnknownRequest),
+            Some((Some(_), None, _)) => Err(Error::<T>::RequestStillPending),
+            Some((_, _, Some(owner))) if owner == sender => Ok(()),
+            Some(_) => Err(Error::<T>::NotRequester),
+        }?;
+
+        // The storage deposit goes along with the request, so that the new owner gets it back when
+        // pruning the request
+        let (depositor, deposit) =
+            Deposits::<T>::get(request_id).ok_or(Error::<T>::UnknownRequest)?;
+
+        // Ensure that the whole deposit is still reserved, as a deposit that is only moved in part
+        // could not be moved back
+        ensure!(
+            T::Currency::reserved_balance(&depositor) >= deposit,
+            Error::<T>::DepositNotTransferred
+        );
+        let leftover = T::Currency::repatriate_reserved(
+            &depositor,
+            &new_owner,
+            deposit,
+            BalanceStatus::Reserved,
+        )?;
+        ensure!(leftover.is_zero(), Error::<T>::DepositNotTransferred);
+        Deposits::<T>::insert(request_id, (new_owner.clone(), deposit));
+
+        Requests::<T>::mutate(request_id, |entry| {
+            if let Some((_, _, owner)) = entry {
+                *owner = Some(new_owner.clone());
             }
-            _ => unreachable!(),
+        });
+        Self::deposit_event(Event::<T>::TransferredRequest {
+            request_id,
+            from: sender,
+            to: new_owner,
+        });
+
+        Ok(())
+    }
+
+    fn set_consumer(
+        origin: O,
+        request_id: RequestId,
+        consumer: Option<T::AccountId>,
+    ) -> DispatchResult
+    where
+        O: Into<Result<frame_system::RawOrigin<T::AccountId>, O>>,
+    {
+        // Ensure that the transaction is signed, and get hold of signer data
+        let sender = ensure_signed(origin)?;
+
+        // Ensure that the request is still waiting for a result, so that nobody can pick the
+        // consumer of a result that is already known, and that it belongs to the sender
+        match Requests::<T>::get(request_id) {
+            None => Err(Error::<T>::UnknownRequest),
+            Some((_, Some(_), _)) => Err(Error::<T>::AlreadyReported),
+            Some((Some(_), None, Some(requester))) if requester == sender => Ok(()),
+            Some((Some(_), None, _)) => Err(Error::<T>::NotRequester),
+            Some((None, None, _)) => Err(Error::<T>::RequestExpired),
+        }?;
+
+        // Ensure that results that go to a feed or back to another chain stay there, as reserving
+        // them would not keep them from being delivered to anyone else
+        if consumer.is_some() {
+            ensure!(
+                !RequestFeeds::<T>::contains_key(request_id)
+                    && !Callbacks::<T>::contains_key(request_id),
+                Error::<T>::UnreservableResult
+            );
         }
-    })
-}
 
-pub fn estimate_report_reward<Balance: frame_support::sp_runtime::traits::Zero>(
-    _bytes_len: usize,
-) -> Balance {
-    // TODO: use Transaction Payment pallet to predict cost of result reporting
-    Balance::zero()
+        match &consumer {
+            Some(consumer) => Consumers::<T>::insert(request_id, consumer),
+            None => Consumers::<T>::remove(request_id),
+        }
+        Self::deposit_event(Event::<T>::SetConsumer {
+            request_id,
+            consumer,
+        });
+
+        Ok(())
+    }
+
+    fn request_randomness(origin: O, reward: BalanceFor<T>) -> DispatchResult
+    where
+        O: Into<Result<frame_system::RawOrigin<T::AccountId>, O>>,
+    {
+        // Ensure that the transaction is signed, and get hold of signer data
+        let sender = ensure_signed(origin)?;
+
+        let bytes = randomness::RANDOMNESS_REQUEST.to_vec();
+        let timeout = T::DefaultRequestTimeout::get();
+        let request_id = reserve_and_post_request::<T>(sender, reward, bytes, timeout)?;
+        RandomnessRequests::<T>::insert(request_id, ());
+
+        Ok(())
+    }
+}
+
+/// Read access to the latest values of data feeds, for the use of other pallets.
+pub trait WitnetFeeds<T>
+where
+    T: Config,
+{
+    /// Get the latest value of a feed, no matter how old it is.
+    ///
+    /// If only the hashes of results are stored, values come without their bytes, which can then
+    /// be checked through `WitnetResults::claim_result` using the request ID of the value.
+    fn latest_value(feed_id: &FeedId) -> Option<FeedEntry<T>>;
+    /// Get the latest value of a feed, as long as it is not older than `max_age`.
+    fn fresh_value(
+        feed_id: &FeedId,
+        max_age: TimestampFor<T>,
+    ) -> Result<FeedEntry<T>, DispatchError>;
+}
+
+impl<T> WitnetFeeds<T> for Pallet<T>
+where
+    T: Config,
+{
+    fn latest_value(feed_id: &FeedId) -> Option<FeedEntry<T>> {
+        Feeds::<T>::get(feed_id)
+    }
+
+    fn fresh_value(
+        feed_id: &FeedId,
+        max_age: TimestampFor<T>,
+    ) -> Result<FeedEntry<T>, DispatchError> {
+        let entry = Feeds::<T>::get(feed_id).ok_or(Error::<T>::UnknownFeed)?;
+
+        // Ensure that the value is recent enough
+        let age = T::TimeProvider::now().saturating_sub((entry.1).0);
+        ensure!(age <= max_age, Error::<T>::StaleFeed);
+
+        Ok(entry)
+    }
+}
+
+/// Report the result of a request on behalf of an operator, be it through a signed transaction or
+/// through an unsigned one that carries a payload signed with the operator key.
+pub(crate) fn report_result_as<T: Config>(
+    reporter: T::AccountId,
+    request_id: RequestId,
+    timestamp: TimestampFor<T>,
+    dr_tx_hash: [u8; 32],
+    result_bytes: Vec<u8>,
+    status: ResultStatus,
+) -> DispatchResult {
+    check_report::<T>(&reporter, request_id, timestamp, &result_bytes)?;
+
+    let bounded_bytes: BoundedVec<_, T::MaxResultSize> = result_bytes
+        .try_into()
+        .map_err(|()| Error::<T>::OversizedResult)?;
+
+    // Ensure that the deadline of the request has not passed, as requests that could not be queued
+    // for expiry are only expired lazily
+    let block = frame_system::Pallet::<T>::block_number();
+    if let Some(deadline) = Deadlines::<T>::get(request_id) {
+        ensure!(block < deadline, Error::<T>::RequestExpired);
+    }
+
+    // Ensure that the result was not produced before the request was posted
+    let posted_at = PostedAt::<T>::get(request_id);
+    if let Some((_, posted_timestamp)) = posted_at {
+        ensure!(
+            timestamp >= posted_timestamp,
+            Error::<T>::ResultPredatesRequest
+        );
+    }
+
+    // Keep the full result around, as it may not be stored
+    let response = (timestamp, dr_tx_hash, bounded_bytes.clone());
+
+    // Do storage related operations in a separate `inner_report_result` function
+    // This will allow reusing part that logic in a future batch reporting method
+    let (mut reward, requester) = inner_report_result::<T>(
+        request_id,
+        timestamp,
+        dr_tx_hash,
+        bounded_bytes,
+        status,
+        true,
+    )?;
+
+    // Push the result to the chain that asked for it, paying for the XCM fees out of the reward
+    let mut fee = Zero::zero();
+    if let Some(callback) = Callbacks::<T>::take(request_id) {
+        if deliver_result::<T>(request_id, response, callback) {
+            fee = T::XcmFee::get().min(reward);
+            reward = reward.saturating_sub(fee);
+        }
+    }
+
+    // Results that are errors are worth less, so part of the reward goes back to the requester
+    if let ResultStatus::Error(error_code) = status {
+        let refund = T::ErrorRefund::get() * reward;
+        T::Currency::unreserve(&requester, refund);
+        reward = reward.saturating_sub(refund);
+        ResultErrors::<T>::insert(request_id, error_code);
+        Pallet::<T>::deposit_event(Event::<T>::ReportedError {
+            request_id,
+            error_code,
+        });
+    }
+
+    // While payouts are paused, the funds stay reserved until someone claims the payout
+    let payout = (requester, reporter.clone(), reward, fee);
+    if PausedOperations::<T>::get().payouts {
+        DeferredPayouts::<T>::insert(request_id, payout);
+    } else {
+        pay_out::<T>(payout)?;
+    }
+
+    // Keep track of the performance of the reporter
+    let latency = posted_at
+        .map(|(posted_block, _)| block.saturating_sub(posted_block))
+        .unwrap_or_default();
+    note_report::<T>(&reporter, reward, latency);
+
+    // Deposit event to signal eventual resolution of the data request
+    Pallet::<T>::deposit_event(Event::<T>::PostedResult {
+        request_id,
+        reporter,
+    });
+
+    Ok(())
+}
+
+/// Run the checks of a report that need no more than a few reads, which are shared by reports
+/// and by the validation of unsigned reports, so that the transaction pool does not fill up with
+/// reports that are bound to fail.
+fn check_report<T: Config>(
+    reporter: &T::AccountId,
+    request_id: RequestId,
+    timestamp: TimestampFor<T>,
+    result_bytes: &[u8],
+) -> Result<(), Error<T>> {
+    // Ensure that reporting has not been paused
+    ensure!(!PausedOperations::<T>::get().reporting, Error::<T>::Paused);
+
+    // Ensure that the sender is entitled to report
+    ensure!(
+        Operators::<T>::contains_key(reporter),
+        Error::<T>::UnauthorizedOperator
+    );
+
+    // Ensure that timestamp is older than current block
+    let now = T::TimeProvider::now();
+    ensure!(timestamp < now, Error::<T>::ResultFromFuture);
+
+    // Ensure that the timestamp is not too far in the past
+    ensure!(
+        now.saturating_sub(timestamp) <= T::MaxResultAge::get(),
+        Error::<T>::ResultTooOld
+    );
+
+    // Ensure that the CBOR bytes are not empty
+    ensure!(!result_bytes.is_empty(), Error::<T>::EmptyResult);
+
+    // Reject oversized results
+    ensure!(
+        result_bytes.len() <= T::MaxResultSize::get() as usize,
+        Error::<T>::OversizedResult
+    );
+
+    // Ensure that the request is not exclusively assigned to a different operator for now
+    if let Some(assigned_operator) = Pallet::<T>::exclusive_operator(request_id) {
+        ensure!(assigned_operator == *reporter, Error::<T>::NotAssignedOperator);
+    }
+
+    Ok(())
+}
+
+/// Check that an unsigned report of a result carries a payload signed with the key of an operator,
+/// that the request is still waiting for a result, and that the report passes the same cheap
+/// checks as any other report.
+///
+/// As only one result per request is ever accepted, the request ID is what the transaction
+/// provides, which keeps the same result from being replayed, and competing reports of the same
+/// result from piling up in the transaction pool.
+pub(crate) fn validate_unsigned_result<T: Config>(
+    payload: &ResultPayload<T>,
+    signature: &OperatorSignatureFor<T>,
+) -> TransactionValidity {
+    // Ensure that the request is still waiting for a result
+    if !PendingRequests::<T>::contains_key(payload.request_id) {
+        return InvalidTransaction::Stale.into();
+    }
+
+    // Ensure that the report would not be rejected right away
+    let check = check_report::<T>(
+        &payload.operator,
+        payload.request_id,
+        payload.timestamp,
+        &payload.result_bytes,
+    );
+    if let Err(error) = check {
+        return match error {
+            Error::<T>::UnauthorizedOperator => InvalidTransaction::BadSigner,
+            Error::<T>::ResultFromFuture => InvalidTransaction::Future,
+            Error::<T>::ResultTooOld => InvalidTransaction::Stale,
+            Error::<T>::OversizedResult => InvalidTransaction::ExhaustsResources,
+            _ => InvalidTransaction::Call,
+        }
+        .into();
+    }
+
+    // Ensure that the payload has been signed with the key of the operator
+    let key = OperatorKeys::<T>::get(&payload.operator).ok_or(InvalidTransaction::BadSigner)?;
+    if !key.verify(&payload.signing_bytes(), signature) {
+        return InvalidTransaction::BadProof.into();
+    }
+
+    ValidTransaction::with_tag_prefix("WitnetOracleResult")
+        .priority(T::UnsignedPriority::get())
+        .and_provides(payload.request_id)
+        .longevity(T::UnsignedLongevity::get())
+        .propagate(true)
+        .build()
+}
+
+/// A hook for reacting to operators that have not reported any result for a whole
+/// `InactivityPeriod`.
+pub trait OnInactiveOperator<T>
+where
+    T: Config,
+{
+    /// Returns the weight consumed, so that it can be accounted for in `on_initialize`.
+    fn on_inactive_operator(operator: &T::AccountId, stats: &OperatorStatsFor<T>) -> Weight;
+}
+
+impl<T> OnInactiveOperator<T> for ()
+where
+    T: Config,
+{
+    fn on_inactive_operator(_operator: &T::AccountId, _stats: &OperatorStatsFor<T>) -> Weight {
+        0
+    }
+}
+
+/// A hook for the off-chain workers of operators, which can relay pending requests to Witnet and
+/// report their results.
+pub trait OffchainRelayer<T>
+where
+    T: Config,
+{
+    fn relay(block_number: T::BlockNumber);
+}
+
+impl<T> OffchainRelayer<T> for ()
+where
+    T: Config,
+{
+    fn relay(_block_number: T::BlockNumber) {}
+}
+
+/// Rotate inactive operators out of the operator set, as long as other operators remain.
+pub struct RemoveInactiveOperators<T>(PhantomData<T>);
+
+impl<T> OnInactiveOperator<T> for RemoveInactiveOperators<T>
+where
+    T: Config,
+{
+    fn on_inactive_operator(operator: &T::AccountId, _stats: &OperatorStatsFor<T>) -> Weight {
+        let db_weight = T::DbWeight::get();
+
+        // Never leave the pallet with too few operators
+        if !can_remove_operator::<T>() {
+            return db_weight.reads(1);
+        }
+
+        Operators::<T>::remove(operator);
+        forget_operator::<T>(operator);
+        Pallet::<T>::deposit_event(Event::<T>::RemovedInactiveOperator {
+            operator: operator.clone(),
+        });
+
+        db_weight.reads_writes(1, 5)
+    }
+}
+
+fn ensure_operator<T, O>(origin: O) -> Result<T::AccountId, DispatchError>
+where
+    T: Config,
+    O: Into<Result<frame_system::RawOrigin<T::AccountId>, O>>,
+{
+    // Ensure that the transaction is signed, and get hold of signer data
+    let sender = ensure_signed(origin)?;
+
+    // Ensure that the sender is entitled to remove an operator
+    ensure!(
+        Operators::<T>::contains_key(&sender),
+        Error::<T>::UnauthorizedOperator
+    );
+
+    Ok(sender)
+}
+
+/// Keep track of the block in which an operator joined, unless it was already an operator.
+pub(crate) fn note_operator_joined<T: Config>(operator: &T::AccountId) {
+    if !OperatorDetails::<T>::contains_key(operator) {
+        let details = OperatorInfo {
+            joined_at: frame_system::Pallet::<T>::block_number(),
+            ..Default::default()
+        };
+        OperatorDetails::<T>::insert(operator, details);
+    }
+}
+
+/// Pay the XCM fee to the fee collector and the rest of the reward to the reporter of a result,
+/// out of the funds that the requester has in reserve.
+fn pay_out<T: Config>(payout: DeferredPayout<T>) -> DispatchResult {
+    let (requester, reporter, reward, fee) = payout;
+
+    if fee > Zero::zero() {
+        let collector = T::XcmFeeCollector::get();
+        T::Currency::repatriate_reserved(&requester, &collector, fee, BalanceStatus::Free)?;
+    }
+
+    if reward > Zero::zero() {
+        // Transfer reserved values from the requester to the reporter
+        T::Currency::repatriate_reserved(&requester, &reporter, reward, BalanceStatus::Free)?;
+        // Send reward to the reporter
+        T::Currency::unreserve(&reporter, reward);
+    }
+
+    Ok(())
+}
+
+/// Make a payout that was deferred because payouts were paused when the result was reported.
+pub(crate) fn claim_payout<T: Config>(request_id: RequestId) -> DispatchResult {
+    // Ensure that payouts have been resumed
+    ensure!(!PausedOperations::<T>::get().payouts, Error::<T>::Paused);
+
+    let payout = DeferredPayouts::<T>::take(request_id).ok_or(Error::<T>::UnknownPayout)?;
+    pay_out::<T>(payout)?;
+    Pallet::<T>::deposit_event(Event::<T>::ClaimedPayout { request_id });
+
+    Ok(())
+}
+
+/// Expire a request whose deadline has passed, but that could not be queued for expiry.
+pub(crate) fn expire_overdue_request<T: Config>(request_id: RequestId) -> DispatchResult {
+    // Ensure that the request is still waiting for a result
+    match Requests::<T>::get(request_id) {
+        None => Err(Error::<T>::UnknownRequest),
+        Some((_, Some(_), _)) => Err(Error::<T>::AlreadyReported),
+        Some((None, None, _)) => Err(Error::<T>::RequestExpired),
+        Some((Some(_), None, _)) => Ok(()),
+    }?;
+
+    // Ensure that the deadline has passed
+    let now = frame_system::Pallet::<T>::block_number();
+    let deadline = Deadlines::<T>::get(request_id).ok_or(Error::<T>::UnknownRequest)?;
+    ensure!(now >= deadline, Error::<T>::RequestNotDue);
+
+    expire_request::<T>(request_id);
+
+    Ok(())
+}
+
+/// Pause or resume some of the operations of the pallet, leaving the others as they were.
+pub(crate) fn set_paused<T: Config>(operations: PauseFlags, paused: bool) {
+    PausedOperations::<T>::mutate(|state| {
+        if operations.posting {
+            state.posting = paused;
+        }
+        if operations.reporting {
+            state.reporting = paused;
+        }
+        if operations.payouts {
+            state.payouts = paused;
+        }
+    });
+
+    let event = if paused {
+        Event::<T>::Paused { operations }
+    } else {
+        Event::<T>::Unpaused { operations }
+    };
+    Pallet::<T>::deposit_event(event);
+}
+
+/// Whether there are more operators than the minimum, which is never less than one.
+fn can_remove_operator<T: Config>() -> bool {
+    Operators::<T>::count() > T::MinOperators::get().max(1)
+}
+
+/// Get rid of everything that is known about a former operator.
+fn forget_operator<T: Config>(operator: &T::AccountId) {
+    OperatorDetails::<T>::remove(operator);
+    OperatorPerformance::<T>::remove(operator);
+    OperatorKeys::<T>::remove(operator);
+}
+
+fn note_report<T: Config>(reporter: &T::AccountId, reward: BalanceFor<T>, latency: T::BlockNumber) {
+    let now = frame_system::Pallet::<T>::block_number();
+    OperatorPerformance::<T>::mutate(reporter, |stats| {
+        stats.reports = stats.reports.saturating_add(1);
+        stats.rewards = stats.rewards.saturating_add(reward);
+        stats.total_latency = stats.total_latency.saturating_add(latency);
+        stats.last_report_at = Some(now);
+    });
+}
+
+/// Count a lost dispute against an operator.
+pub(crate) fn record_lost_dispute<T: Config>(operator: T::AccountId) -> DispatchResult {
+    ensure!(
+        Operators::<T>::contains_key(&operator),
+        Error::<T>::UnknownOperator
+    );
+
+    OperatorPerformance::<T>::mutate(&operator, |stats| {
+        stats.disputes_lost = stats.disputes_lost.saturating_add(1);
+    });
+    Pallet::<T>::deposit_event(Event::<T>::LostDispute { operator });
+
+    Ok(())
+}
+
+/// Pass all the operators that have not reported any result for a whole `InactivityPeriod` to the
+/// `OnInactiveOperator` hook.
+///
+/// Returns the weight consumed, so that it can be accounted for in `on_initialize`.
+pub(crate) fn check_operators_activity<T: Config>(now: T::BlockNumber) -> Weight {
+    let db_weight = T::DbWeight::get();
+    let period = T::InactivityPeriod::get();
+
+    // Collect the operators beforehand, as the hook may be removing some of them
+    let operators: Vec<T::AccountId> = Operators::<T>::iter_keys().collect();
+    let mut weight = db_weight.reads(operators.len() as u64);
+    for operator in operators {
+        let stats = OperatorPerformance::<T>::get(&operator);
+        // Operators that never reported a result are measured from the block they joined in
+        let last_active = stats.last_report_at.unwrap_or_else(|| {
+            OperatorDetails::<T>::get(&operator)
+                .map(|details| details.joined_at)
+                .unwrap_or_default()
+        });
+        weight = weight.saturating_add(db_weight.reads(2));
+
+        if now.saturating_sub(last_active) >= period {
+            weight = weight.saturating_add(T::OnInactiveOperator::on_inactive_operator(
+                &operator, &stats,
+            ));
+        }
+    }
+
+    weight
+}
+
+/// Post a request on behalf of another chain, whose sovereign account pays for the reward.
+pub(crate) fn post_remote_request<T: Config>(
+    location: MultiLocation,
+    reward: BalanceFor<T>,
+    bytes: Vec<u8>,
+) -> DispatchResult {
+    let requester = T::LocationToAccountId::convert_ref(&location)
+        .map_err(|()| Error::<T>::UnknownSovereignAccount)?;
+
+    // Ensure that the reward covers the XCM fees for sending the result back
+    ensure!(reward >= T::XcmFee::get(), Error::<T>::UnderpayingCallback);
+
+    // Ensure that the chain tells which of its calls receives results, as results can only be sent
+    // back to the chain that posted the request
+    let call_index =
+        <T::XcmCallbackIndex as sp_runtime::traits::Convert<_, _>>::convert(location.clone())
+            .ok_or(Error::<T>::BadCallbackDestination)?;
+
+    let timeout = T::DefaultRequestTimeout::get();
+    let request_id = reserve_and_post_request::<T>(requester, reward, bytes, timeout)?;
+    Callbacks::<T>::insert(request_id, (location, call_index));
+
+    Ok(())
+}
+
+fn reserve_and_post_request<T: Config>(
+    requester: T::AccountId,
+    reward: BalanceFor<T>,
+    bytes: Vec<u8>,
+    timeout: T::BlockNumber,
+) -> Result<RequestId, DispatchError> {
+    // Reject oversized requests
+    let bytes: BoundedVec<_, T::MaxRequestSize> = bytes
+        .try_into()
+        .map_err(|()| Error::<T>::OversizedRequest)?;
+
+    // Check that the report reward foreseeably covers cost of reporting
+    let required_reward = estimate_report_reward::<BalanceFor<T>>(bytes.len());
+    ensure!(reward >= required_reward, Error::<T>::UnderpayingRequest);
+
+    reserve_and_post_bounded_request::<T>(requester, reward, bytes, timeout)
+}
+
+fn reserve_and_post_bounded_request<T: Config>(
+    requester: T::AccountId,
+    reward: BalanceFor<T>,
+    bytes: BoundedVec<u8, T::MaxRequestSize>,
+    timeout: T::BlockNumber,
+) -> Result<RequestId, DispatchError> {
+    // Try to put aside the reward to be paid later to the reporter of the result
+    T::Currency::reserve(&requester, reward)?;
+
+    // Do storage related operations in a separate `inner_post_request` function
+    // This allows reusing that logic for requests posted by subscriptions
+    inner_post_request::<T>(requester.clone(), reward, bytes, timeout).map_err(|error| {
+        T::Currency::unreserve(&requester, reward);
+        error
+    })
+}
+
+fn inner_post_request<T: Config>(
+    requester: T::AccountId,
+    reward: BalanceFor<T>,
+    bytes: BoundedVec<u8, T::MaxRequestSize>,
+    timeout: T::BlockNumber,
+) -> Result<RequestId, DispatchError> {
+    // Ensure that posting has not been paused
+    ensure!(!PausedOperations::<T>::get().posting, Error::<T>::Paused);
+
+    // Use next request ID
+    let request_id = NextRequestId::<T>::get();
+
+    // Ensure that the requester is not flooding the pallet with requests
+    let open_requests = OpenRequests::<T>::get(&requester);
+    ensure!(
+        open_requests < T::MaxOpenRequests::get(),
+        Error::<T>::TooManyOpenRequests
+    );
+
+    // Try to put aside the storage deposit, which is given back once the request is pruned
+    let deposit = request_deposit::<T>(bytes.len());
+    T::Currency::reserve(&requester, deposit)?;
+
+    // Schedule the expiry of the request, which may end up being later than requested if
+    // there is no more room in the queue for the block of the deadline
+    let now = frame_system::Pallet::<T>::block_number();
+    let deadline = schedule_expiry::<T>(request_id, now.saturating_add(timeout));
+    Deadlines::<T>::insert(request_id, deadline);
+    Deposits::<T>::insert(request_id, (requester.clone(), deposit));
+    OpenRequests::<T>::insert(&requester, open_requests.saturating_add(1));
+
+    // Store request and deposit event to signal readiness for fulfillment
+    let request_entry: RequestEntry<T> = (Some((bytes, reward)), None, Some(requester.clone()));
+    Requests::<T>::insert(request_id, request_entry);
+    PostedAt::<T>::insert(request_id, (now, T::TimeProvider::now()));
+    PendingRequests::<T>::insert(request_id, ());
+
+    // Give the next operator in turn some time to report the result before anyone else can
+    let window = T::ExclusivityWindow::get();
+    let assigned_operator = if window.is_zero() {
+        None
+    } else {
+        next_operator_in_turn::<T>()
+    };
+    if let Some(operator) = &assigned_operator {
+        Assignments::<T>::insert(request_id, (operator.clone(), now.saturating_add(window)));
+    }
+
+    Pallet::<T>::deposit_event(Event::<T>::PostedRequest {
+        request_id,
+        requester,
+        assigned_operator,
+    });
+
+    // Increase next request ID
+    NextRequestId::<T>::put(request_id.wrapping_add(1));
+
+    Ok(request_id)
+}
+
+fn inner_report_result<T: Config>(
+    request_id: u64,
+    timestamp: TimestampFor<T>,
+    dr_tx_hash: [u8; 32],
+    result_bytes: BoundedVec<u8, T::MaxResultSize>,
+    status: ResultStatus,
+    drop: bool,
+) -> Result<(BalanceFor<T>, T::AccountId), Error<T>> {
+    // Retrieve request info from storage, fail if unknown
+    <Requests<T>>::try_mutate(request_id, |entry| {
+        match entry {
+            // Ensure the request exists
+            None => Err(Error::<T>::UnknownRequest),
+            // Ensure the request had not been already reported
+            Some((_, Some(_), ..)) => Err(Error::<T>::AlreadyReported),
+            // Ensure the request has not expired
+            Some((None, None, _)) => Err(Error::<T>::RequestExpired),
+            // If the query is still there, we can operate, otherwise do nothing
+            Some((query_option, report_option @ None, requester_option)) => {
+                let reward = query_option
+                    .as_ref()
+                    .map(|(_, reward)| *reward)
+                    .ok_or(Error::<T>::RequestExpired)?;
+                // Entries without a requester are not known to belong to anyone
+                let requester = requester_option.clone().ok_or(Error::<T>::UnknownRequest)?;
+                // If drop is set to true, remove the query when inserting the report, but keep the
+                // requester, who stays the owner of the result
+                if drop {
+                    *query_option = None;
+                }
+                // Randomness requests renew the randomness that the pallet hands out, unless their
+                // result is reserved to a consumer
+                if RandomnessRequests::<T>::take(request_id).is_some()
+                    && status == ResultStatus::Success
+                    && !Consumers::<T>::contains_key(request_id)
+                {
+                    update_randomness::<T>(request_id, &result_bytes);
+                }
+                // In hash-only mode, the full result only goes out through an event
+                let stored_bytes = if T::HashOnlyResults::get() {
+                    ResultHashes::<T>::insert(request_id, blake2_256(&result_bytes));
+                    Pallet::<T>::deposit_event(Event::<T>::PublishedResult {
+                        request_id,
+                        result_bytes: result_bytes.into_inner(),
+                    });
+                    Default::default()
+                } else {
+                    result_bytes
+                };
+                // Keep track of the latest value of the feed this request belongs to, if any, which
+                // in hash-only mode is stored without its bytes too
+                // Errors are not values, so they never make it into feeds
+                if let Some(feed_id) = RequestFeeds::<T>::take(request_id) {
+                    if status == ResultStatus::Success {
+                        let response = (timestamp, dr_tx_hash, stored_bytes.clone());
+                        update_feed::<T>(feed_id, request_id, response);
+                    }
+                }
+                // Insert the report, after which the request is no longer open
+                *report_option = Some((timestamp, dr_tx_hash, stored_bytes));
+                close_request::<T>(request_id, &requester);
+
+                Ok((reward, requester))
+            }
+        }
+    })
+}
+
+/// Pick the operator that comes right after the one that was assigned the latest request.
+fn next_operator_in_turn<T: Config>() -> Option<T::AccountId> {
+    // Start over from the first operator once the end of the operator set is reached
+    let next = LastAssignedOperator::<T>::get()
+        .and_then(|last| {
+            let starting_key = Operators::<T>::hashed_key_for(last);
+            Operators::<T>::iter_keys_from(starting_key).next()
+        })
+        .or_else(|| Operators::<T>::iter_keys().next());
+    LastAssignedOperator::<T>::set(next.clone());
+
+    next
+}
+
+/// Post a new request on behalf of a subscription, and schedule its next execution.
+///
+/// Returns the weight consumed, so that it can be accounted for in `on_initialize`.
+pub(crate) fn trigger_subscription<T: Config>(
+    subscription_id: SubscriptionId,
+    now: T::BlockNumber,
+) -> Weight {
+    let db_weight = T::DbWeight::get();
+
+    // Subscriptions that have been cancelled are simply skipped
+    let mut subscription = match Subscriptions::<T>::get(subscription_id) {
+        Some(subscription) => subscription,
+        None => return db_weight.reads(1),
+    };
+
+    // The reward is already reserved as part of the budget, so no need to reserve it again
+    // If the request cannot be posted this time, the subscription simply skips this period
+    if let Ok(request_id) = inner_post_request::<T>(
+        subscription.owner.clone(),
+        subscription.reward,
+        subscription.bytes.clone(),
+        T::DefaultRequestTimeout::get(),
+    ) {
+        RequestFeeds::<T>::insert(request_id, feed_id_for(&subscription.bytes));
+        subscription.budget = subscription.budget.saturating_sub(subscription.reward);
+        Pallet::<T>::deposit_event(Event::<T>::TriggeredSubscription {
+            subscription_id,
+            request_id,
+        });
+    }
+
+    // Schedule the next request, unless the budget has run out or the queue is full
+    // Subscriptions without a reward never run out of budget, so they come to an end right away
+    let next_block = now.saturating_add(subscription.period);
+    if !subscription.reward.is_zero()
+        && subscription.budget >= subscription.reward
+        && schedule_subscription::<T>(subscription_id, next_block).is_ok()
+    {
+        Subscriptions::<T>::insert(subscription_id, subscription);
+    } else {
+        Subscriptions::<T>::remove(subscription_id);
+        T::Currency::unreserve(&subscription.owner, subscription.budget);
+        Pallet::<T>::deposit_event(Event::<T>::ExhaustedSubscription { subscription_id });
+    }
+
+    db_weight.reads_writes(9, 14)
+}
+
+fn schedule_subscription<T: Config>(
+    subscription_id: SubscriptionId,
+    at: T::BlockNumber,
+) -> Result<(), Error<T>> {
+    SubscriptionQueue::<T>::try_mutate(at, |queue| {
+        queue
+            .try_push(subscription_id)
+            .map_err(|()| Error::<T>::SubscriptionQueueFull)
+    })
+}
+
+/// Mark as expired all the requests whose deadline is the current block, and give the rewards
+/// back to their requesters.
+///
+/// Returns the weight consumed, so that it can be accounted for in `on_initialize`.
+pub(crate) fn expire_requests<T: Config>(now: T::BlockNumber) -> Weight {
+    let db_weight = T::DbWeight::get();
+
+    // The size of the queue is bounded, and so is the weight of this function
+    let due = ExpiryQueue::<T>::take(now);
+    let mut weight = db_weight.reads_writes(1, 1);
+    for request_id in due {
+        weight = weight.saturating_add(expire_request::<T>(request_id));
+    }
+
+    weight
+}
+
+fn expire_request<T: Config>(request_id: RequestId) -> Weight {
+    let db_weight = T::DbWeight::get();
+
+    // Entries are left untouched unless they expire, as those that cannot be decoded (i.e. while a
+    // storage migration is ongoing) would be removed otherwise
+    let expired = Requests::<T>::try_mutate(request_id, |entry| match entry {
+        // Only requests that are still waiting for a result can expire
+        Some((query_option, None, Some(requester))) => {
+            let (_, reward) = query_option.take().ok_or(())?;
+            Ok((requester.clone(), reward))
+        }
+        _ => Err(()),
+    });
+
+    // Requests that got resolved in time are simply skipped
+    let (requester, reward) = match expired {
+        Ok(expired) => expired,
+        Err(()) => return db_weight.reads(1),
+    };
+
+    release_expired_request::<T>(request_id, &requester, reward);
+
+    db_weight.reads_writes(3, 10)
+}
+
+/// Give the reward of a request whose query has just been dropped back to its requester, and get
+/// rid of all the bookkeeping of the request.
+pub(crate) fn release_expired_request<T: Config>(
+    request_id: RequestId,
+    requester: &T::AccountId,
+    reward: BalanceFor<T>,
+) {
+    T::Currency::unreserve(requester, reward);
+    close_request::<T>(request_id, requester);
+    RequestFeeds::<T>::remove(request_id);
+    RandomnessRequests::<T>::remove(request_id);
+    Callbacks::<T>::remove(request_id);
+    Pallet::<T>::deposit_event(Event::<T>::ExpiredRequest { request_id });
+}
+
+/// Get rid of all the bookkeeping of a request that is no longer waiting for a result.
+fn close_request<T: Config>(request_id: RequestId, requester: &T::AccountId) {
+    PostedAt::<T>::remove(request_id);
+    Deadlines::<T>::remove(request_id);
+    Assignments::<T>::remove(request_id);
+    PendingRequests::<T>::remove(request_id);
+    OpenRequests::<T>::mutate(requester, |open| *open = open.saturating_sub(1));
+    // Templates can be unregistered once none of the requests posted from them is pending
+    if let Some(template_id) = RequestTemplates::<T>::get(request_id) {
+        PendingTemplateRequests::<T>::mutate_exists(template_id, |count| {
+            *count = count
+                .map(|count| count.saturating_sub(1))
+                .filter(|count| *count > 0);
+        });
+    }
+}
+
+/// Queue the expiry of a request, and get its deadline.
+///
+/// A full queue must not keep anyone from posting requests, so if there is no room in the queue
+/// for a few blocks, the request is not queued at all, and it only expires lazily: reports are
+/// rejected once the deadline has passed, and anyone can then expire it through
+/// `expire_request`.
+fn schedule_expiry<T: Config>(request_id: RequestId, deadline: T::BlockNumber) -> T::BlockNumber {
+    let mut at = deadline;
+    for _ in 0..MAX_EXPIRY_SCHEDULING_ATTEMPTS {
+        let pushed = ExpiryQueue::<T>::mutate(at, |queue| queue.try_push(request_id).is_ok());
+        if pushed {
+            return at;
+        }
+        at = at.saturating_add(One::one());
+    }
+
+    deadline
+}
+
+/// Send the result of a request through XCM, as a call to the callback of the chain that posted it.
+///
+/// Returns whether the message could be sent.
+fn deliver_result<T: Config>(
+    request_id: RequestId,
+    response: Response<T>,
+    callback: XcmCallback,
+) -> bool {
+    let (destination, call_index) = callback;
+
+    // The callback call is made of its index followed by the SCALE encoded result
+    let mut call = call_index.to_vec();
+    (request_id, response).encode_to(&mut call);
+    let fee_asset = T::XcmFeeAsset::get();
+    let message = Xcm(vec![
+        WithdrawAsset(fee_asset.clone().into()),
+        BuyExecution {
+            fees: fee_asset,
+            weight_limit: Unlimited,
+        },
+        // Callbacks get the location of this chain as their origin, rather than a signed origin of
+        // its sovereign account
+        Transact {
+            origin_type: OriginKind::Xcm,
+            require_weight_at_most: T::XcmCallbackWeight::get(),
+            call: call.into(),
+        },
+    ]);
+
+    // Failing to deliver the result must not revert the report, as the result is stored anyway
+    match T::XcmSender::send_xcm(destination.clone(), message) {
+        Ok(()) => {
+            Pallet::<T>::deposit_event(Event::<T>::DeliveredResult {
+                request_id,
+                destination,
+            });
+
+            true
+        }
+        Err(_) => {
+            Pallet::<T>::deposit_event(Event::<T>::FailedResultDelivery {
+                request_id,
+                destination,
+            });
+
+            false
+        }
+    }
+}
+
+/// Replace the pending randomness with that of the result of a randomness request, unless the
+/// result is not the byte string that randomness requests resolve to.
+fn update_randomness<T: Config>(request_id: RequestId, result_bytes: &[u8]) {
+    if let Some(randomness) = randomness::parse_randomness(result_bytes) {
+        let now = frame_system::Pallet::<T>::block_number();
+        PendingRandomness::<T>::put((request_id, randomness, now));
+    }
+}
+
+/// Hand out the pending randomness once it is `RandomnessDelay` blocks old, mixed with the hash of
+/// the parent block, so that the operator that reported it cannot tell in advance what the random
+/// values will be.
+pub(crate) fn release_randomness<T: Config>(now: T::BlockNumber) -> Weight {
+    let db_weight = T::DbWeight::get();
+
+    let (request_id, randomness, reported_at) = match PendingRandomness::<T>::get() {
+        Some(pending) => pending,
+        None => return db_weight.reads(1),
+    };
+    if now < reported_at.saturating_add(T::RandomnessDelay::get()) {
+        return db_weight.reads(1);
+    }
+
+    let parent_hash = frame_system::Pallet::<T>::parent_hash();
+    let randomness = blake2_256(&(randomness, parent_hash).encode());
+    LatestRandomness::<T>::put((randomness, now));
+    PendingRandomness::<T>::kill();
+    Pallet::<T>::deposit_event(Event::<T>::UpdatedRandomness { request_id });
+
+    db_weight.reads_writes(2, 3)
+}
+
+fn update_feed<T: Config>(feed_id: FeedId, request_id: RequestId, response: Response<T>) {
+    Feeds::<T>::mutate(feed_id, |feed| {
+        // Results may be reported out of order, so never replace a value with an older one
+        if let Some((_, (latest_timestamp, ..))) = feed {
+            if *latest_timestamp > response.0 {
+                return;
+            }
+        }
+        *feed = Some((request_id, response));
+        Pallet::<T>::deposit_event(Event::<T>::UpdatedFeed {
+            feed_id,
+            request_id,
+        });
+    });
+}
+
+/// Access to the results of requests, for the use of other pallets.
+pub trait WitnetResults<T>
+where
+    T: Config,
+{
+    /// Check the full bytes of the result of a request against the ones found in storage, or
+    /// against their hash if only the hash is stored, and get the timestamp and the Witnet
+    /// transaction hash of the result.
+    fn claim_result(
+        request_id: RequestId,
+        result_bytes: &[u8],
+    ) -> Result<(TimestampFor<T>, [u8; 32]), DispatchError>;
+    /// Get the result of a request as found in storage, i.e. without its bytes if only the hash is
+    /// stored. Results that have a consumer can only be read by that consumer.
+    fn read_result(
+        reader: &T::AccountId,
+        request_id: RequestId,
+    ) -> Result<Response<T>, DispatchError>;
+    /// Get the result of a request like `read_result` does, but only once, and only if the
+    /// `consumer` is the one that the requester chose. Useful for results that must not be reused,
+    /// such as random numbers.
+    fn consume_result(
+        consumer: &T::AccountId,
+        request_id: RequestId,
+    ) -> Result<Response<T>, DispatchError>;
+}
+
+impl<T> WitnetResults<T> for Pallet<T>
+where
+    T: Config,
+{
+    fn claim_result(
+        request_id: RequestId,
+        result_bytes: &[u8],
+    ) -> Result<(TimestampFor<T>, [u8; 32]), DispatchError> {
+        let (timestamp, dr_tx_hash, stored_bytes) = match Requests::<T>::get(request_id) {
+            None => Err(Error::<T>::UnknownRequest),
+            Some((_, None, _)) => Err(Error::<T>::UnresolvedRequest),
+            Some((_, Some(response), _)) => Ok(response),
+        }?;
+
+        // Ensure that the result is not reserved to a specific consumer
+        ensure!(
+            !Consumers::<T>::contains_key(request_id),
+            Error::<T>::NotConsumer
+        );
+
+        // Ensure that the bytes are those of the actual result
+        let matches = match ResultHashes::<T>::get(request_id) {
+            Some(hash) => blake2_256(result_bytes) == hash,
+            None => stored_bytes.as_slice() == result_bytes,
+        };
+        ensure!(matches, Error::<T>::ResultMismatch);
+
+        Ok((timestamp, dr_tx_hash))
+    }
+
+    fn read_result(
+        reader: &T::AccountId,
+        request_id: RequestId,
+    ) -> Result<Response<T>, DispatchError> {
+        let response = match Requests::<T>::get(request_id) {
+            None => Err(Error::<T>::UnknownRequest),
+            Some((_, None, _)) => Err(Error::<T>::UnresolvedRequest),
+            Some((_, Some(response), _)) => Ok(response),
+        }?;
+
+        // Ensure that the result has not been used up, and that the reader can read it at all
+        ensure!(
+            !ConsumedResults::<T>::contains_key(request_id),
+            Error::<T>::ResultConsumed
+        );
+        if let Some(consumer) = Consumers::<T>::get(request_id) {
+            ensure!(&consumer == reader, Error::<T>::NotConsumer);
+        }
+
+        Ok(response)
+    }
+
+    fn consume_result(
+        consumer: &T::AccountId,
+        request_id: RequestId,
+    ) -> Result<Response<T>, DispatchError> {
+        let response = Self::read_result(consumer, request_id)?;
+
+        // Ensure that there is a consumer at all, as results that anyone can read cannot be
+        // consumed
+        ensure!(
+            Consumers::<T>::contains_key(request_id),
+            Error::<T>::NotConsumer
+        );
+
+        ConsumedResults::<T>::insert(request_id, ());
+        Self::deposit_event(Event::<T>::ConsumedResult {
+            request_id,
+            consumer: consumer.clone(),
+        });
+
+        Ok(response)
+    }
+}
+
+/// Randomness backed by the latest result of a randomness request, so that Witnet can be plugged
+/// in as the randomness source of other pallets.
+impl<T> Randomness<T::Hash, T::BlockNumber> for Pallet<T>
+where
+    T: Config,
+{
+    /// Mix the latest randomness with the subject, and get the number of the block from which that
+    /// randomness is handed out, after which it is known to everyone. Until the randomness of the
+    /// first randomness request is handed out, the output is the hash of the subject alone, and
+    /// the block number is zero, so it must not be relied upon.
+    fn random(subject: &[u8]) -> (T::Hash, T::BlockNumber) {
+        match LatestRandomness::<T>::get() {
+            Some((randomness, since)) => (T::Hashing::hash_of(&(randomness, subject)), since),
+            None => (T::Hashing::hash(subject), Zero::zero()),
+        }
+    }
+}
+
+/// Compute the storage deposit for a request with a specific number of bytes.
+pub fn request_deposit<T: Config>(bytes_len: usize) -> BalanceFor<T> {
+    let bytes_len = BalanceFor::<T>::from(bytes_len as u32);
+
+    T::RequestDepositBase::get()
+        .saturating_add(T::RequestDepositPerByte::get().saturating_mul(bytes_len))
+}
+
+/// Derive the identifier of the feed that requests with some specific bytes belong to.
+pub fn feed_id_for(bytes: &[u8]) -> FeedId {
+    blake2_256(bytes)
+}
+
+/// Estimate the reward that a request of a given size needs for reporting its result to pay off.
+///
+/// Successful reports pay no transaction fees, so reporting a result costs nothing on this chain
+/// whatever its size, and any reward covers it. What resolving the request costs on Witnet is up
+/// to the requester and the operators to agree on.
+pub fn estimate_report_reward<Balance: frame_support::sp_runtime::traits::Zero>(
+    _bytes_len: usize,
+) -> Balance {
+    Balance::zero()
+}
+
+/// The weight of posting a request of a given size.
+///
+/// Posting requests through `post_request` is charged up front for the largest request, and then
+/// refunded the difference with the weight of the bytes that they actually take.
+pub fn post_request_weight<T: Config>(bytes: usize) -> Weight {
+    T::WeightInfo::post_request(bounded_len(bytes, T::MaxRequestSize::get()))
+}
+
+/// The weight of failing to post a request of a given size.
+pub fn failed_post_request_weight<T: Config>(bytes: usize) -> Weight {
+    T::WeightInfo::failed_post_request(bounded_len(bytes, T::MaxRequestSize::get()))
+}
+
+/// The weight of posting a request of a given size with a custom timeout.
+pub fn post_request_with_timeout_weight<T: Config>(bytes: usize) -> Weight {
+    T::WeightInfo::post_request_with_timeout(bounded_len(bytes, T::MaxRequestSize::get()))
+}
+
+/// The weight of posting a request of a given size on behalf of another chain.
+pub fn post_remote_request_weight<T: Config>(bytes: usize) -> Weight {
+    T::WeightInfo::post_remote_request(bounded_len(bytes, T::MaxRequestSize::get()))
+}
+
+/// The weight of posting a feed request of a given size, whose bytes also get hashed.
+pub fn post_feed_request_weight<T: Config>(bytes: usize) -> Weight {
+    T::WeightInfo::post_feed_request(bounded_len(bytes, T::MaxRequestSize::get()))
+}
+
+/// The weight of subscribing to a request of a given size.
+pub fn subscribe_weight<T: Config>(bytes: usize) -> Weight {
+    T::WeightInfo::subscribe(bounded_len(bytes, T::MaxRequestSize::get()))
+}
+
+/// The weight of registering a template of a given size, whose bytes also get checked and hashed.
+pub fn register_template_weight<T: Config>(bytes: usize) -> Weight {
+    T::WeightInfo::register_template(bounded_len(bytes, T::MaxRequestSize::get()))
+}
+
+/// The weight of posting a request from a template with some arguments.
+///
+/// Filling never takes more than the template and the biggest request, whatever the template, so
+/// the weight only depends on how many arguments are copied over, and on how many bytes they take.
+pub fn post_request_from_template_weight<T: Config>(args: &TemplateArgs<T>) -> Weight {
+    let bytes = args.iter().map(|arg| arg.len()).sum();
+    let max_bytes = templates::MAX_TEMPLATE_ARGS.saturating_mul(T::MaxRequestSize::get());
+    let args = bounded_len(args.len(), templates::MAX_TEMPLATE_ARGS);
+
+    T::WeightInfo::post_request_from_template(args, bounded_len(bytes, max_bytes))
+}
+
+/// The weight of reporting a result of a given size, which is never lighter than that of
+/// reporting the result of a randomness request.
+pub fn report_result_weight<T: Config>(bytes: usize) -> Weight {
+    T::WeightInfo::report_result(bounded_len(bytes, T::MaxResultSize::get()))
+        .max(T::WeightInfo::report_randomness())
+}
+
+/// The weight of failing to report a result of a given size.
+pub fn failed_report_result_weight<T: Config>(bytes: usize) -> Weight {
+    T::WeightInfo::failed_report_result(bounded_len(bytes, T::MaxResultSize::get()))
+}
+
+/// Attach the actual weight of a call to its result, depending on whether it succeeded.
+pub fn with_actual_weight(
+    result: DispatchResult,
+    succeeded: Weight,
+    failed: Weight,
+) -> DispatchResultWithPostInfo {
+    match result {
+        Ok(()) => Ok(Some(succeeded).into()),
+        Err(error) => Err(DispatchErrorWithPostInfo {
+            post_info: Some(failed).into(),
+            error,
+        }),
+    }
+}
+
+// Bytes beyond the maximum size are rejected right away, so they never cost more than the maximum
+fn bounded_len(bytes: usize, max_bytes: u32) -> u32 {
+    u32::try_from(bytes).unwrap_or(u32::MAX).min(max_bytes)
 }
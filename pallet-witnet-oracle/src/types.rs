@@ -1,5 +1,7 @@
 use super::*;
 
+pub use pallet_witnet_oracle_primitives::ResultStatus;
+
 pub type BalanceFor<T> = <<T as Config>::Currency as frame_support::traits::Currency<
     <T as frame_system::Config>::AccountId,
 >>::Balance;
@@ -7,11 +9,12 @@ pub type BalanceFor<T> = <<T as Config>::Currency as frame_support::traits::Curr
 pub type TimestampFor<T> = <<T as Config>::TimeProvider as frame_support::traits::Time>::Moment;
 
 pub type Query<T> = (
-    frame_support::BoundedVec<u8, <T as Config>::MaxByteSize>,
+    frame_support::BoundedVec<u8, <T as Config>::MaxRequestSize>,
     BalanceFor<T>,
 );
 pub type RequestId = u64;
 
+/// The bytes and reward of a request while it waits for a result, its result, and its owner.
 pub type RequestEntry<T> = (
     Option<Query<T>>,
     Option<Response<T>>,
@@ -21,5 +24,157 @@ pub type RequestEntry<T> = (
 pub type Response<T> = (
     TimestampFor<T>,
     [u8; 32],
-    frame_support::BoundedVec<u8, <T as Config>::MaxByteSize>,
+    frame_support::BoundedVec<u8, <T as Config>::MaxResultSize>,
+);
+
+pub type FeedId = [u8; 32];
+
+pub type TemplateId = [u8; 32];
+
+/// The arguments that fill the placeholders of a template, none of which can be bigger than a
+/// request.
+pub type TemplateArgs<T> = frame_support::BoundedVec<
+    frame_support::BoundedVec<u8, <T as Config>::MaxRequestSize>,
+    frame_support::traits::ConstU32<{ crate::templates::MAX_TEMPLATE_ARGS }>,
+>;
+
+/// The owner, the bytes and the storage deposit of a request template.
+pub type TemplateEntry<T> = (
+    <T as frame_system::Config>::AccountId,
+    frame_support::BoundedVec<u8, <T as Config>::MaxRequestSize>,
+    BalanceFor<T>,
 );
+
+pub type FeedEntry<T> = (RequestId, Response<T>);
+
+pub type SubscriptionId = u64;
+
+/// The location of a chain, and the index of the call that receives results on that chain.
+pub type XcmCallback = (MultiLocation, [u8; 2]);
+
+/// The requester, the reporter, the reward and the XCM fee of a result whose payout is pending.
+pub type DeferredPayout<T> = (
+    <T as frame_system::Config>::AccountId,
+    <T as frame_system::Config>::AccountId,
+    BalanceFor<T>,
+    BalanceFor<T>,
+);
+
+/// The storage map that a storage migration is going through, and the last key of that map that it
+/// has migrated, if any.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub enum MigrationCursor {
+    Requests(Option<RequestId>),
+    Subscriptions(Option<SubscriptionId>),
+    Feeds(Option<FeedId>),
+}
+
+/// The operations of the pallet that can be paused separately during an incident.
+#[derive(Encode, Decode, Clone, Copy, Default, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct PauseFlags {
+    /// Posting new requests, including those of subscriptions.
+    pub posting: bool,
+    /// Reporting results.
+    pub reporting: bool,
+    /// Paying rewards and XCM fees out of the funds reserved by requesters.
+    pub payouts: bool,
+}
+
+#[derive(
+    Encode, Decode, CloneNoBound, PartialEqNoBound, EqNoBound, RuntimeDebugNoBound, TypeInfo,
+)]
+#[scale_info(skip_type_params(T))]
+pub struct Subscription<T: Config> {
+    /// The account that pays for every request posted by the subscription.
+    pub owner: <T as frame_system::Config>::AccountId,
+    /// The bytes of the data request that gets posted on every period.
+    pub bytes: frame_support::BoundedVec<u8, <T as Config>::MaxRequestSize>,
+    /// The reward offered for reporting the result of each of the requests.
+    pub reward: BalanceFor<T>,
+    /// What is left of the prepaid budget, which is kept in reserve.
+    pub budget: BalanceFor<T>,
+    /// How many blocks to wait between requests.
+    pub period: <T as frame_system::Config>::BlockNumber,
+}
+
+/// A result reported through an unsigned transaction, on behalf of an operator that signs it with
+/// its operator key rather than paying for a signed transaction.
+#[derive(
+    Encode, Decode, CloneNoBound, PartialEqNoBound, EqNoBound, RuntimeDebugNoBound, TypeInfo,
+)]
+#[scale_info(skip_type_params(T))]
+pub struct ResultPayload<T: Config> {
+    /// The operator that reports the result, and gets the reward.
+    pub operator: <T as frame_system::Config>::AccountId,
+    pub request_id: RequestId,
+    pub timestamp: TimestampFor<T>,
+    pub dr_tx_hash: [u8; 32],
+    pub result_bytes: Vec<u8>,
+    pub status: ResultStatus,
+}
+
+/// The prefix of the bytes that operators sign, so that their signatures are good for nothing else.
+pub const RESULT_PAYLOAD_PREFIX: &[u8] = b"witnet-oracle:result";
+
+impl<T: Config> ResultPayload<T> {
+    /// The bytes that the operator signs, which tie the payload to this pallet, and to this chain
+    /// through its genesis hash.
+    pub fn signing_bytes(&self) -> Vec<u8> {
+        let genesis_hash =
+            frame_system::Pallet::<T>::block_hash(frame_support::sp_runtime::traits::Zero::zero());
+
+        (RESULT_PAYLOAD_PREFIX, genesis_hash, self).encode()
+    }
+}
+
+pub type OperatorSignatureFor<T> = <<T as Config>::OperatorKey as RuntimeAppPublic>::Signature;
+
+/// Public information about an operator, for the use of dashboards and relayers.
+#[derive(Encode, Decode, Clone, Default, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct OperatorInfo<BlockNumber, Text> {
+    /// A human readable name.
+    pub name: Text,
+    /// The public key hash of the Witnet address that the operator uses for relaying requests.
+    pub witnet_pkh: [u8; 20],
+    /// The URL of the relayer run by the operator.
+    pub url: Text,
+    /// The block in which the operator joined.
+    pub joined_at: BlockNumber,
+}
+
+pub type OperatorInfoFor<T> = OperatorInfo<
+    <T as frame_system::Config>::BlockNumber,
+    frame_support::BoundedVec<u8, <T as Config>::MaxOperatorInfoLength>,
+>;
+
+/// Performance statistics of an operator, for telling apart the operators that actually relay.
+#[derive(Encode, Decode, Clone, Default, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct OperatorStats<BlockNumber, Balance> {
+    /// How many results the operator has reported.
+    pub reports: u32,
+    /// The sum of all the rewards that the operator has earned.
+    pub rewards: Balance,
+    /// The sum of the blocks elapsed between posting each request and reporting its result.
+    pub total_latency: BlockNumber,
+    /// How many disputes over its results the operator has lost.
+    pub disputes_lost: u32,
+    /// The block in which the operator reported its latest result, if any.
+    pub last_report_at: Option<BlockNumber>,
+}
+
+impl<BlockNumber, Balance> OperatorStats<BlockNumber, Balance>
+where
+    BlockNumber: frame_support::sp_runtime::traits::AtLeast32BitUnsigned + Copy,
+{
+    /// The average number of blocks elapsed between posting a request and reporting its result.
+    pub fn average_latency(&self) -> BlockNumber {
+        if self.reports == 0 {
+            return BlockNumber::zero();
+        }
+
+        self.total_latency / BlockNumber::from(self.reports)
+    }
+}
+
+pub type OperatorStatsFor<T> =
+    OperatorStats<<T as frame_system::Config>::BlockNumber, BalanceFor<T>>;
@@ -0,0 +1,169 @@
+//! Weights for pallet_witnet_oracle.
+//!
+//! These are estimates, not measurements. The reads and writes are counted by hand from the
+//! storage items that the heaviest path of each call touches, and the execution times are rough
+//! figures that have not been measured on reference hardware. Runtimes should rather use the
+//! weights of their own run of the benchmarks in `benchmarking.rs`, which a node built with
+//! `--features runtime-benchmarks` outputs in this very format through its `benchmark pallet`
+//! subcommand.
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use frame_support::sp_std::marker::PhantomData;
+
+/// Weight functions needed for pallet_witnet_oracle.
+pub trait WeightInfo {
+	fn post_request(b: u32, ) -> Weight;
+	fn failed_post_request(b: u32, ) -> Weight;
+	fn report_result(b: u32, ) -> Weight;
+	fn report_randomness() -> Weight;
+	fn failed_report_result(b: u32, ) -> Weight;
+	fn post_request_with_timeout(b: u32, ) -> Weight;
+	fn post_remote_request(b: u32, ) -> Weight;
+	fn post_feed_request(b: u32, ) -> Weight;
+	fn subscribe(b: u32, ) -> Weight;
+	fn register_template(b: u32, ) -> Weight;
+	fn post_request_from_template(a: u32, b: u32, ) -> Weight;
+}
+
+/// Weights for pallet_witnet_oracle, using the database weights of the runtime.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	fn post_request(b: u32, ) -> Weight {
+		(10_000_000 as Weight)
+			.saturating_add((2_000 as Weight).saturating_mul(b as Weight))
+			.saturating_add(T::DbWeight::get().reads(20 as Weight))
+			.saturating_add(T::DbWeight::get().writes(21 as Weight))
+	}
+	fn failed_post_request(b: u32, ) -> Weight {
+		(10_000_000 as Weight)
+			.saturating_add((2_000 as Weight).saturating_mul(b as Weight))
+			.saturating_add(T::DbWeight::get().reads(4 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	fn report_result(b: u32, ) -> Weight {
+		(10_000_000 as Weight)
+			.saturating_add((2_000 as Weight).saturating_mul(b as Weight))
+			.saturating_add(T::DbWeight::get().reads(21 as Weight))
+			.saturating_add(T::DbWeight::get().writes(17 as Weight))
+	}
+	fn report_randomness() -> Weight {
+		(10_068_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(21 as Weight))
+			.saturating_add(T::DbWeight::get().writes(18 as Weight))
+	}
+	fn failed_report_result(b: u32, ) -> Weight {
+		(10_000_000 as Weight)
+			.saturating_add((2_000 as Weight).saturating_mul(b as Weight))
+			.saturating_add(T::DbWeight::get().reads(8 as Weight))
+	}
+	fn post_request_with_timeout(b: u32, ) -> Weight {
+		(10_000_000 as Weight)
+			.saturating_add((2_000 as Weight).saturating_mul(b as Weight))
+			.saturating_add(T::DbWeight::get().reads(20 as Weight))
+			.saturating_add(T::DbWeight::get().writes(21 as Weight))
+	}
+	fn post_remote_request(b: u32, ) -> Weight {
+		(10_000_000 as Weight)
+			.saturating_add((2_000 as Weight).saturating_mul(b as Weight))
+			.saturating_add(T::DbWeight::get().reads(20 as Weight))
+			.saturating_add(T::DbWeight::get().writes(22 as Weight))
+	}
+	fn post_feed_request(b: u32, ) -> Weight {
+		(10_000_000 as Weight)
+			.saturating_add((4_000 as Weight).saturating_mul(b as Weight))
+			.saturating_add(T::DbWeight::get().reads(20 as Weight))
+			.saturating_add(T::DbWeight::get().writes(22 as Weight))
+	}
+	fn subscribe(b: u32, ) -> Weight {
+		(10_000_000 as Weight)
+			.saturating_add((2_000 as Weight).saturating_mul(b as Weight))
+			.saturating_add(T::DbWeight::get().reads(4 as Weight))
+			.saturating_add(T::DbWeight::get().writes(4 as Weight))
+	}
+	fn register_template(b: u32, ) -> Weight {
+		(10_000_000 as Weight)
+			.saturating_add((4_000 as Weight).saturating_mul(b as Weight))
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
+	fn post_request_from_template(a: u32, b: u32, ) -> Weight {
+		(20_000_000 as Weight)
+			.saturating_add((100_000 as Weight).saturating_mul(a as Weight))
+			.saturating_add((2_000 as Weight).saturating_mul(b as Weight))
+			.saturating_add(T::DbWeight::get().reads(22 as Weight))
+			.saturating_add(T::DbWeight::get().writes(23 as Weight))
+	}
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	fn post_request(b: u32, ) -> Weight {
+		(10_000_000 as Weight)
+			.saturating_add((2_000 as Weight).saturating_mul(b as Weight))
+			.saturating_add(RocksDbWeight::get().reads(20 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(21 as Weight))
+	}
+	fn failed_post_request(b: u32, ) -> Weight {
+		(10_000_000 as Weight)
+			.saturating_add((2_000 as Weight).saturating_mul(b as Weight))
+			.saturating_add(RocksDbWeight::get().reads(4 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	fn report_result(b: u32, ) -> Weight {
+		(10_000_000 as Weight)
+			.saturating_add((2_000 as Weight).saturating_mul(b as Weight))
+			.saturating_add(RocksDbWeight::get().reads(21 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(17 as Weight))
+	}
+	fn report_randomness() -> Weight {
+		(10_068_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(21 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(18 as Weight))
+	}
+	fn failed_report_result(b: u32, ) -> Weight {
+		(10_000_000 as Weight)
+			.saturating_add((2_000 as Weight).saturating_mul(b as Weight))
+			.saturating_add(RocksDbWeight::get().reads(8 as Weight))
+	}
+	fn post_request_with_timeout(b: u32, ) -> Weight {
+		(10_000_000 as Weight)
+			.saturating_add((2_000 as Weight).saturating_mul(b as Weight))
+			.saturating_add(RocksDbWeight::get().reads(20 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(21 as Weight))
+	}
+	fn post_remote_request(b: u32, ) -> Weight {
+		(10_000_000 as Weight)
+			.saturating_add((2_000 as Weight).saturating_mul(b as Weight))
+			.saturating_add(RocksDbWeight::get().reads(20 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(22 as Weight))
+	}
+	fn post_feed_request(b: u32, ) -> Weight {
+		(10_000_000 as Weight)
+			.saturating_add((4_000 as Weight).saturating_mul(b as Weight))
+			.saturating_add(RocksDbWeight::get().reads(20 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(22 as Weight))
+	}
+	fn subscribe(b: u32, ) -> Weight {
+		(10_000_000 as Weight)
+			.saturating_add((2_000 as Weight).saturating_mul(b as Weight))
+			.saturating_add(RocksDbWeight::get().reads(4 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(4 as Weight))
+	}
+	fn register_template(b: u32, ) -> Weight {
+		(10_000_000 as Weight)
+			.saturating_add((4_000 as Weight).saturating_mul(b as Weight))
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+	}
+	fn post_request_from_template(a: u32, b: u32, ) -> Weight {
+		(20_000_000 as Weight)
+			.saturating_add((100_000 as Weight).saturating_mul(a as Weight))
+			.saturating_add((2_000 as Weight).saturating_mul(b as Weight))
+			.saturating_add(RocksDbWeight::get().reads(22 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(23 as Weight))
+	}
+}
@@ -0,0 +1,196 @@
+//! The connection to the parachain, through which requests are read and results reported.
+//!
+//! Calls and events are built and decoded against the metadata that the node serves at runtime,
+//! so there is no need to generate code for every runtime that includes the pallet. The parachain
+//! is expected to use 32 bytes account IDs and the signed extensions of Polkadot.
+
+use anyhow::{anyhow, Context};
+use futures::{future::BoxFuture, stream::BoxStream, FutureExt, StreamExt};
+pub use pallet_witnet_oracle_primitives::ResultStatus;
+use subxt::{
+    dynamic::Value,
+    events::StaticEvent,
+    ext::{
+        codec::{Decode, Encode},
+        sp_core::{sr25519, Bytes, Pair},
+        sp_runtime::AccountId32,
+    },
+    rpc::rpc_params,
+    tx::PairSigner,
+    OnlineClient, PolkadotConfig,
+};
+
+use crate::{config::ChainConfig, state::RequestId};
+
+/// How many request IDs to ask for at once when listing pending requests.
+const PENDING_REQUESTS_PAGE_SIZE: u32 = 100;
+
+/// The name of the pallet in the `construct_runtime!` of the parachain.
+const PALLET: &str = "Witnet";
+
+/// The `PostedRequest` event, decoded in full to match its layout. The operator that the request
+/// is assigned to is checked right before relaying the request instead, through
+/// `Chain::exclusive_operator`, as its exclusivity window may be over by then.
+#[derive(Debug, Decode)]
+pub struct PostedRequest {
+    pub request_id: RequestId,
+    pub requester: AccountId32,
+    pub assigned_operator: Option<AccountId32>,
+}
+
+impl StaticEvent for PostedRequest {
+    const PALLET: &'static str = PALLET;
+    const EVENT: &'static str = "PostedRequest";
+}
+
+pub struct Chain {
+    api: OnlineClient<PolkadotConfig>,
+    signer: PairSigner<PolkadotConfig, sr25519::Pair>,
+}
+
+impl Chain {
+    pub async fn connect(config: &ChainConfig) -> anyhow::Result<Self> {
+        let pair = sr25519::Pair::from_string(&config.operator_seed, None)
+            .map_err(|e| anyhow!("Invalid operator seed: {:?}", e))?;
+        let api = OnlineClient::<PolkadotConfig>::from_url(&config.url)
+            .await
+            .with_context(|| format!("Failed to connect to the parachain at {}", config.url))?;
+
+        Ok(Self {
+            api,
+            signer: PairSigner::new(pair),
+        })
+    }
+
+    /// Subscribe to the IDs of the requests posted in every finalized block.
+    pub async fn subscribe_posted_requests(
+        &self,
+    ) -> anyhow::Result<BoxStream<'static, anyhow::Result<Vec<RequestId>>>> {
+        let events = self.api.events().subscribe_finalized().await?;
+
+        Ok(events
+            .map(|events| -> anyhow::Result<Vec<RequestId>> {
+                let request_ids = events?
+                    .find::<PostedRequest>()
+                    .map(|event| event.map(|event| event.request_id))
+                    .collect::<Result<_, _>>()?;
+
+                Ok(request_ids)
+            })
+            .boxed())
+    }
+
+    /// Get the IDs of all the requests that are still waiting for a result.
+    pub async fn pending_requests(&self) -> anyhow::Result<Vec<RequestId>> {
+        let mut pending = Vec::new();
+        let mut start_after = None;
+
+        loop {
+            let page: Vec<RequestId> = self
+                .runtime_api_call(
+                    "WitnetOracleApi_pending_requests",
+                    (start_after, PENDING_REQUESTS_PAGE_SIZE),
+                )
+                .await?;
+            start_after = page.last().copied();
+            let last_page = page.len() < PENDING_REQUESTS_PAGE_SIZE as usize;
+            pending.extend(page);
+
+            if last_page {
+                return Ok(pending);
+            }
+        }
+    }
+
+    /// Get the bytes of a request, as long as it is still waiting for a result.
+    pub async fn request_bytes(&self, request_id: RequestId) -> anyhow::Result<Option<Vec<u8>>> {
+        self.runtime_api_call("WitnetOracleApi_request_bytes", request_id)
+            .await
+    }
+
+    /// Get the operator that a request is exclusively assigned to, as long as nobody else can
+    /// report its result yet.
+    pub async fn exclusive_operator(
+        &self,
+        request_id: RequestId,
+    ) -> anyhow::Result<Option<AccountId32>> {
+        self.runtime_api_call("WitnetOracleApi_exclusive_operator", request_id)
+            .await
+    }
+
+    /// Whether a request is exclusively assigned to an operator other than the one that this
+    /// relayer reports results as, which is the only one that can report its result for now.
+    pub async fn is_assigned_elsewhere(&self, request_id: RequestId) -> anyhow::Result<bool> {
+        let exclusive_operator = self.exclusive_operator(request_id).await?;
+
+        Ok(is_assigned_elsewhere(
+            exclusive_operator.as_ref(),
+            self.signer.account_id(),
+        ))
+    }
+
+    /// Submit the report of the result of a request, and get a future that resolves once the
+    /// report is finalized.
+    pub async fn submit_report(
+        &self,
+        request_id: RequestId,
+        timestamp: u64,
+        dr_tx_hash: [u8; 32],
+        result_bytes: Vec<u8>,
+        status: ResultStatus,
+    ) -> anyhow::Result<BoxFuture<'static, anyhow::Result<()>>> {
+        let call = subxt::dynamic::tx(
+            PALLET,
+            "report_result",
+            vec![
+                Value::u128(request_id.into()),
+                Value::u128(timestamp.into()),
+                Value::from_bytes(dr_tx_hash),
+                Value::from_bytes(result_bytes),
+                status_value(status),
+            ],
+        );
+
+        let progress = self
+            .api
+            .tx()
+            .sign_and_submit_then_watch_default(&call, &self.signer)
+            .await?;
+
+        Ok(async move {
+            progress.wait_for_finalized_success().await?;
+
+            Ok(())
+        }
+        .boxed())
+    }
+
+    async fn runtime_api_call<R: Decode>(
+        &self,
+        function: &str,
+        args: impl Encode,
+    ) -> anyhow::Result<R> {
+        let result: Bytes = self
+            .api
+            .rpc()
+            .request("state_call", rpc_params![function, Bytes(args.encode())])
+            .await?;
+
+        R::decode(&mut &result[..]).with_context(|| format!("Malformed result of {}", function))
+    }
+}
+
+/// Whether the exclusive operator of a request, if any, is someone other than `operator`.
+pub fn is_assigned_elsewhere(
+    exclusive_operator: Option<&AccountId32>,
+    operator: &AccountId32,
+) -> bool {
+    matches!(exclusive_operator, Some(exclusive_operator) if exclusive_operator != operator)
+}
+
+fn status_value(status: ResultStatus) -> Value {
+    match status {
+        ResultStatus::Success => Value::unnamed_variant("Success", []),
+        ResultStatus::Error(code) => Value::unnamed_variant("Error", [Value::u128(code.into())]),
+    }
+}
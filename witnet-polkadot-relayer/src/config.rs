@@ -0,0 +1,89 @@
+//! The configuration of the relayer, which is read from a TOML file.
+
+use std::{
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use anyhow::Context;
+use pallet_witnet_oracle_primitives::witnet;
+use serde::Deserialize;
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    /// The file that keeps track of the requests being relayed, so that they survive restarts.
+    pub state_path: PathBuf,
+    pub chain: ChainConfig,
+    pub witnet: WitnetConfig,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct ChainConfig {
+    /// The WebSocket URL of a node of the parachain.
+    pub url: String,
+    /// The secret URI of the sr25519 key of the operator account, e.g. a mnemonic or `//Alice`.
+    pub operator_seed: String,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct WitnetConfig {
+    /// The address of the JSON-RPC server of the Witnet node, e.g. `127.0.0.1:21338`.
+    pub node_address: String,
+    /// The fee that the Witnet node pays for every data request, in nanowits.
+    #[serde(default = "default_fee")]
+    pub fee: u64,
+    /// How often to check the Witnet node for the tallies of data requests, in seconds.
+    #[serde(default = "default_poll_interval")]
+    pub poll_interval: u64,
+    /// The UNIX timestamp of checkpoint zero of the Witnet chain, in seconds.
+    #[serde(default = "default_checkpoint_zero_timestamp")]
+    pub checkpoint_zero_timestamp: u64,
+    /// The number of seconds between checkpoints of the Witnet chain.
+    #[serde(default = "default_checkpoints_period")]
+    pub checkpoints_period: u64,
+}
+
+impl Config {
+    pub fn from_file(path: &Path) -> anyhow::Result<Self> {
+        let config = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read the configuration at {:?}", path))?;
+
+        toml::from_str(&config)
+            .with_context(|| format!("Failed to parse the configuration at {:?}", path))
+    }
+}
+
+impl WitnetConfig {
+    pub fn poll_interval(&self) -> Duration {
+        Duration::from_secs(self.poll_interval)
+    }
+
+    /// The UNIX timestamp of a checkpoint of the Witnet chain, in milliseconds, as the pallet
+    /// expects from `pallet_timestamp`.
+    pub fn checkpoint_timestamp(&self, checkpoint: u64) -> u64 {
+        // Timestamps beyond what fits are not valid anyway, so they get rejected by the pallet
+        self.checkpoint_zero_timestamp
+            .saturating_add(checkpoint.saturating_mul(self.checkpoints_period))
+            .saturating_mul(1_000)
+    }
+}
+
+fn default_fee() -> u64 {
+    1
+}
+
+fn default_poll_interval() -> u64 {
+    30
+}
+
+// The defaults of the Witnet mainnet
+fn default_checkpoint_zero_timestamp() -> u64 {
+    witnet::CHECKPOINT_ZERO_TIMESTAMP
+}
+
+fn default_checkpoints_period() -> u64 {
+    witnet::CHECKPOINTS_PERIOD
+}
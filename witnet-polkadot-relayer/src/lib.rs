@@ -0,0 +1,10 @@
+//! A relayer that operators of the Witnet oracle pallet can run next to a Witnet node.
+//!
+//! It watches the parachain for requests, sends them to Witnet, waits for their tallies to be
+//! final, and reports their results back to the parachain.
+
+pub mod chain;
+pub mod config;
+pub mod relayer;
+pub mod state;
+pub mod witnet;
@@ -0,0 +1,19 @@
+use std::path::PathBuf;
+
+use witnet_polkadot_relayer::{config::Config, relayer::Relayer};
+
+/// The configuration file that is used unless another one is given as the first argument.
+const DEFAULT_CONFIG_PATH: &str = "relayer.toml";
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
+
+    let config_path = std::env::args_os()
+        .nth(1)
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(DEFAULT_CONFIG_PATH));
+    let config = Config::from_file(&config_path)?;
+
+    Relayer::new(config).await?.run().await
+}
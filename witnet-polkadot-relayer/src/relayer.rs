@@ -0,0 +1,194 @@
+//! The main loop of the relayer.
+//!
+//! Every request goes through the same steps: it is picked up from a `PostedRequest` event, or
+//! from the list of pending requests when the relayer starts, then it is sent to Witnet, and its
+//! result is reported once the block that includes its tally is final. Reports are awaited
+//! alongside everything else, so that waiting for them to be finalized holds nothing up.
+
+use std::collections::HashSet;
+
+use anyhow::anyhow;
+use futures::{future::BoxFuture, stream::FuturesUnordered, FutureExt, StreamExt};
+use pallet_witnet_oracle_primitives::witnet::result_status;
+
+use crate::{
+    chain::Chain,
+    config::Config,
+    state::{RequestId, RequestState, State},
+    witnet::WitnetClient,
+};
+
+/// A report that has been submitted, which resolves once it is finalized.
+type Report = BoxFuture<'static, (RequestId, anyhow::Result<()>)>;
+
+pub struct Relayer {
+    config: Config,
+    chain: Chain,
+    witnet: WitnetClient,
+    state: State,
+    /// The reports that are waiting to be finalized.
+    reports: FuturesUnordered<Report>,
+    /// The requests whose reports are waiting to be finalized, which are not relayed again.
+    reporting: HashSet<RequestId>,
+}
+
+impl Relayer {
+    pub async fn new(config: Config) -> anyhow::Result<Self> {
+        let state = State::load(&config.state_path)?;
+        let chain = Chain::connect(&config.chain).await?;
+        let witnet = WitnetClient::new(&config.witnet.node_address);
+
+        Ok(Self {
+            config,
+            chain,
+            witnet,
+            state,
+            reports: FuturesUnordered::new(),
+            reporting: HashSet::new(),
+        })
+    }
+
+    pub async fn run(mut self) -> anyhow::Result<()> {
+        let mut posted_requests = self.chain.subscribe_posted_requests().await?;
+
+        // Catch up with the requests that were posted while the relayer was not running
+        for request_id in self.chain.pending_requests().await? {
+            self.track(request_id)?;
+        }
+
+        let mut ticker = tokio::time::interval(self.config.witnet.poll_interval());
+        loop {
+            tokio::select! {
+                request_ids = posted_requests.next() => {
+                    let request_ids = request_ids
+                        .ok_or_else(|| anyhow!("The subscription to the parachain ended"))??;
+                    for request_id in request_ids {
+                        self.track(request_id)?;
+                    }
+                }
+                Some((request_id, report)) = self.reports.next(), if !self.reports.is_empty() => {
+                    if let Err(e) = self.finish_report(request_id, report).await {
+                        log::warn!(
+                            "Failed to report the result of request #{}: {:?}",
+                            request_id,
+                            e
+                        );
+                    }
+                }
+                _ = ticker.tick() => self.relay_all().await,
+            }
+        }
+    }
+
+    /// Move every request one step forward, i.e. send it to Witnet or report its result.
+    pub async fn relay_all(&mut self) {
+        for (request_id, state) in self.state.requests() {
+            if self.reporting.contains(&request_id) {
+                continue;
+            }
+            // Failing to relay a request must not get in the way of relaying the others
+            if let Err(e) = self.relay(request_id, state).await {
+                log::warn!("Failed to relay request #{}: {:?}", request_id, e);
+            }
+        }
+    }
+
+    fn track(&mut self, request_id: RequestId) -> anyhow::Result<()> {
+        if self.state.get(request_id).is_some() {
+            return Ok(());
+        }
+
+        log::info!("Found new request #{}", request_id);
+        self.state.insert(request_id, RequestState::New)
+    }
+
+    async fn relay(&mut self, request_id: RequestId, state: RequestState) -> anyhow::Result<()> {
+        let dr_tx_hash = match state {
+            RequestState::New => {
+                // Requests assigned to another operator are left to them until their exclusivity
+                // window is over, which never opens again, so that results are only ever sent for
+                // requests that this relayer can report
+                if self.chain.is_assigned_elsewhere(request_id).await? {
+                    log::debug!("Request #{} is assigned to another operator", request_id);
+                    return Ok(());
+                }
+                // Requests that got resolved or expired in the meantime are not relayed anymore
+                let bytes = match self.chain.request_bytes(request_id).await? {
+                    Some(bytes) => bytes,
+                    None => return self.state.remove(request_id),
+                };
+                let dr_tx_hash = self
+                    .witnet
+                    .send_request(&bytes, self.config.witnet.fee)
+                    .await?;
+                log::info!(
+                    "Sent request #{} to Witnet in {}",
+                    request_id,
+                    hex::encode(dr_tx_hash)
+                );
+
+                return self
+                    .state
+                    .insert(request_id, RequestState::Sent { dr_tx_hash });
+            }
+            RequestState::Sent { dr_tx_hash } => dr_tx_hash,
+        };
+
+        let tally = match self.witnet.data_request_tally(&dr_tx_hash).await? {
+            Some(tally) => tally,
+            None => return Ok(()),
+        };
+        let checkpoint = match self.witnet.confirmed_checkpoint(&tally.block_hash).await? {
+            Some(checkpoint) => checkpoint,
+            None => return Ok(()),
+        };
+
+        let timestamp = self.config.witnet.checkpoint_timestamp(checkpoint);
+        let status = result_status(&tally.result_bytes);
+        let report = self
+            .chain
+            .submit_report(
+                request_id,
+                timestamp,
+                dr_tx_hash,
+                tally.result_bytes,
+                status,
+            )
+            .await;
+
+        match report {
+            Ok(finalized) => {
+                self.reporting.insert(request_id);
+                self.reports
+                    .push(async move { (request_id, finalized.await) }.boxed());
+
+                Ok(())
+            }
+            Err(e) => self.finish_report(request_id, Err(e)).await,
+        }
+    }
+
+    /// Stop tracking a request once its result is reported, or if someone else reported it.
+    async fn finish_report(
+        &mut self,
+        request_id: RequestId,
+        report: anyhow::Result<()>,
+    ) -> anyhow::Result<()> {
+        self.reporting.remove(&request_id);
+
+        match report {
+            Ok(()) => {
+                log::info!("Reported the result of request #{}", request_id);
+                self.state.remove(request_id)
+            }
+            Err(e) => {
+                // Someone else may have reported the result already
+                if self.chain.request_bytes(request_id).await?.is_none() {
+                    self.state.remove(request_id)?;
+                }
+
+                Err(e)
+            }
+        }
+    }
+}
@@ -0,0 +1,80 @@
+//! The requests that are being relayed, which are persisted to a JSON file on every change so
+//! that the relayer can pick up where it left off, without sending any request to Witnet twice.
+
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+
+pub type RequestId = u64;
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RequestState {
+    /// The request has not been sent to Witnet yet.
+    New,
+    /// The request has been sent to Witnet in the data request transaction with this hash.
+    Sent {
+        #[serde(with = "hex::serde")]
+        dr_tx_hash: [u8; 32],
+    },
+}
+
+#[derive(Debug, Default)]
+pub struct State {
+    path: PathBuf,
+    requests: BTreeMap<RequestId, RequestState>,
+}
+
+impl State {
+    /// Load the state from a file, or start with no requests if the file does not exist yet.
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let requests = match fs::read(path) {
+            Ok(bytes) => serde_json::from_slice(&bytes)
+                .with_context(|| format!("Failed to parse the state at {:?}", path))?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => BTreeMap::new(),
+            Err(e) => {
+                return Err(e).with_context(|| format!("Failed to read the state at {:?}", path))
+            }
+        };
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            requests,
+        })
+    }
+
+    pub fn get(&self, request_id: RequestId) -> Option<&RequestState> {
+        self.requests.get(&request_id)
+    }
+
+    pub fn requests(&self) -> Vec<(RequestId, RequestState)> {
+        self.requests
+            .iter()
+            .map(|(request_id, state)| (*request_id, state.clone()))
+            .collect()
+    }
+
+    pub fn insert(&mut self, request_id: RequestId, state: RequestState) -> anyhow::Result<()> {
+        self.requests.insert(request_id, state);
+        self.save()
+    }
+
+    pub fn remove(&mut self, request_id: RequestId) -> anyhow::Result<()> {
+        self.requests.remove(&request_id);
+        self.save()
+    }
+
+    fn save(&self) -> anyhow::Result<()> {
+        // Write to a temporary file first, so that a crash never leaves a truncated state behind
+        let tmp_path = self.path.with_extension("tmp");
+        fs::write(&tmp_path, serde_json::to_vec_pretty(&self.requests)?)
+            .with_context(|| format!("Failed to write the state to {:?}", tmp_path))?;
+        fs::rename(&tmp_path, &self.path)
+            .with_context(|| format!("Failed to write the state to {:?}", self.path))
+    }
+}
@@ -0,0 +1,115 @@
+//! A client of the JSON-RPC server of a Witnet node, which takes one JSON message per line over
+//! plain TCP.
+//!
+//! Requests are sent through `sendRequest`, which takes the JSON form of a `DataRequestOutput` and
+//! a fee, as built by the pallet out of the protobuf bytes that requesters post, and returns the
+//! hash of the data request transaction. Tallies are found in the `dataRequestReport` of a data
+//! request, along with the hash of the block that includes them, and `getBlock` tells whether that
+//! block has been confirmed by a superblock, and at which checkpoint.
+
+use anyhow::{anyhow, bail, Context};
+use serde_json::{json, Value};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    net::TcpStream,
+};
+
+/// The result of a data request, along with the hash of the block that includes it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Tally {
+    pub result_bytes: Vec<u8>,
+    pub block_hash: [u8; 32],
+}
+
+pub struct WitnetClient {
+    address: String,
+}
+
+impl WitnetClient {
+    pub fn new(address: impl Into<String>) -> Self {
+        Self {
+            address: address.into(),
+        }
+    }
+
+    /// Send a request to Witnet, and get the hash of its data request transaction.
+    pub async fn send_request(&self, bytes: &[u8], fee: u64) -> anyhow::Result<[u8; 32]> {
+        let params = pallet_witnet_oracle_primitives::witnet::send_request_params(bytes, fee)
+            .map_err(|()| anyhow!("The request is not a valid data request output"))?;
+        let params = serde_json::from_slice(&params)?;
+        let dr_tx_hash = self.call("sendRequest", params).await?;
+
+        parse_hash(&dr_tx_hash)
+    }
+
+    /// Get the tally of a data request, if it has already been resolved.
+    pub async fn data_request_tally(&self, dr_tx_hash: &[u8; 32]) -> anyhow::Result<Option<Tally>> {
+        let report = self
+            .call("dataRequestReport", json!([hex::encode(dr_tx_hash)]))
+            .await?;
+
+        let tally = match report.get("tally") {
+            Some(Value::Null) | None => return Ok(None),
+            Some(tally) => tally,
+        };
+        let result_bytes = serde_json::from_value(tally["tally"].clone())
+            .context("Malformed tally in dataRequestReport")?;
+        let block_hash = parse_hash(&report["block_hash_tally_tx"])?;
+
+        Ok(Some(Tally {
+            result_bytes,
+            block_hash,
+        }))
+    }
+
+    /// Get the checkpoint of a block, but only once the block is final, i.e. once it has been
+    /// confirmed by a superblock.
+    pub async fn confirmed_checkpoint(&self, block_hash: &[u8; 32]) -> anyhow::Result<Option<u64>> {
+        let block = self
+            .call("getBlock", json!([hex::encode(block_hash)]))
+            .await?;
+
+        if block["confirmed"] != Value::Bool(true) {
+            return Ok(None);
+        }
+
+        block["block_header"]["beacon"]["checkpoint"]
+            .as_u64()
+            .map(Some)
+            .ok_or_else(|| anyhow!("Malformed block in getBlock"))
+    }
+
+    async fn call(&self, method: &str, params: Value) -> anyhow::Result<Value> {
+        let stream = TcpStream::connect(&self.address)
+            .await
+            .with_context(|| format!("Failed to connect to the Witnet node at {}", self.address))?;
+        let (reader, mut writer) = stream.into_split();
+
+        let request = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+        let mut line = serde_json::to_vec(&request)?;
+        line.push(b'\n');
+        writer.write_all(&line).await?;
+
+        let mut line = String::new();
+        BufReader::new(reader).read_line(&mut line).await?;
+        let mut response: Value = serde_json::from_str(&line)
+            .with_context(|| format!("Malformed response to {}", method))?;
+
+        if let Some(error) = response.get("error") {
+            bail!("The Witnet node failed to {}: {}", method, error);
+        }
+
+        Ok(response["result"].take())
+    }
+}
+
+fn parse_hash(hash: &Value) -> anyhow::Result<[u8; 32]> {
+    let hash = hash
+        .as_str()
+        .ok_or_else(|| anyhow!("Expected a hex encoded hash, got {}", hash))?;
+
+    let mut bytes = [0; 32];
+    hex::decode_to_slice(hash, &mut bytes)?;
+
+    Ok(bytes)
+}
@@ -0,0 +1,14 @@
+use subxt::ext::sp_runtime::AccountId32;
+use witnet_polkadot_relayer::chain::is_assigned_elsewhere;
+
+#[test]
+fn test_is_assigned_elsewhere() {
+    let operator = AccountId32::new([1; 32]);
+    let other_operator = AccountId32::new([2; 32]);
+
+    // Requests assigned to nobody, or to the operator itself, can be relayed right away
+    assert!(!is_assigned_elsewhere(None, &operator));
+    assert!(!is_assigned_elsewhere(Some(&operator), &operator));
+    // Requests assigned to someone else are left to them
+    assert!(is_assigned_elsewhere(Some(&other_operator), &operator));
+}
@@ -0,0 +1,175 @@
+//! A mocked Witnet node, which speaks the same JSON-RPC dialect as the real one and resolves data
+//! requests only when told to.
+//!
+//! Like the real one, it takes the JSON form of a `DataRequestOutput` and a fee in `sendRequest`,
+//! reports the tally of a data request and the hash of the block that includes it in
+//! `dataRequestReport`, and tells the checkpoint of a block and whether it has been confirmed by a
+//! superblock in `getBlock`.
+
+use std::{
+    collections::HashMap,
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+};
+
+use serde_json::{json, Value};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    net::TcpListener,
+};
+
+#[derive(Default)]
+struct MockState {
+    /// The data request transaction hashes, data request outputs and fees of the requests that
+    /// have been sent.
+    requests: Vec<([u8; 32], Value, u64)>,
+    /// The result bytes and block hashes of the tallies, by data request transaction hash.
+    tallies: HashMap<[u8; 32], (Vec<u8>, [u8; 32])>,
+    /// The checkpoints of the blocks, and whether they have been confirmed.
+    blocks: HashMap<[u8; 32], (u64, bool)>,
+}
+
+#[derive(Clone)]
+pub struct MockWitnetNode {
+    pub address: SocketAddr,
+    state: Arc<Mutex<MockState>>,
+}
+
+impl MockWitnetNode {
+    pub async fn start() -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let node = Self {
+            address: listener.local_addr().unwrap(),
+            state: Default::default(),
+        };
+
+        let state = node.state.clone();
+        tokio::spawn(async move {
+            loop {
+                let (stream, _) = listener.accept().await.unwrap();
+                let state = state.clone();
+                tokio::spawn(async move {
+                    let (reader, mut writer) = stream.into_split();
+                    let mut lines = BufReader::new(reader).lines();
+                    while let Ok(Some(line)) = lines.next_line().await {
+                        let request: Value = serde_json::from_str(&line).unwrap();
+                        let result = handle(&state, &request["method"], &request["params"]);
+                        let response =
+                            json!({ "jsonrpc": "2.0", "id": request["id"], "result": result });
+                        let mut line = serde_json::to_vec(&response).unwrap();
+                        line.push(b'\n');
+                        writer.write_all(&line).await.unwrap();
+                    }
+                });
+            }
+        });
+
+        node
+    }
+
+    /// The hashes, data request outputs and fees of the requests sent so far.
+    pub fn requests(&self) -> Vec<([u8; 32], Value, u64)> {
+        self.state.lock().unwrap().requests.clone()
+    }
+
+    /// Resolve a data request in a block that is not confirmed yet.
+    pub fn resolve(&self, dr_tx_hash: [u8; 32], result_bytes: Vec<u8>, checkpoint: u64) {
+        let block_hash = [checkpoint as u8; 32];
+        let mut state = self.state.lock().unwrap();
+        state.tallies.insert(dr_tx_hash, (result_bytes, block_hash));
+        state.blocks.insert(block_hash, (checkpoint, false));
+    }
+
+    /// Confirm the block that includes the tally of a data request.
+    pub fn confirm(&self, dr_tx_hash: [u8; 32]) {
+        let mut state = self.state.lock().unwrap();
+        let (_, block_hash) = state.tallies[&dr_tx_hash];
+        state.blocks.get_mut(&block_hash).unwrap().1 = true;
+    }
+}
+
+fn handle(state: &Mutex<MockState>, method: &Value, params: &Value) -> Value {
+    let mut state = state.lock().unwrap();
+
+    match method.as_str().unwrap() {
+        "sendRequest" => {
+            let dro = params["dro"].clone();
+            assert!(dro["data_request"]["retrieve"].is_array());
+            let fee = params["fee"].as_u64().unwrap();
+            let dr_tx_hash = [state.requests.len() as u8 + 1; 32];
+            state.requests.push((dr_tx_hash, dro, fee));
+
+            json!(hex::encode(dr_tx_hash))
+        }
+        "dataRequestReport" => {
+            let dr_tx_hash = parse_hash(&params[0]);
+            match state.tallies.get(&dr_tx_hash) {
+                Some((result_bytes, block_hash)) => json!({
+                    "tally": { "tally": result_bytes },
+                    "block_hash_tally_tx": hex::encode(block_hash),
+                }),
+                None => json!({ "tally": null, "block_hash_tally_tx": null }),
+            }
+        }
+        "getBlock" => {
+            let (checkpoint, confirmed) = state.blocks[&parse_hash(&params[0])];
+
+            json!({
+                "block_header": { "beacon": { "checkpoint": checkpoint } },
+                "confirmed": confirmed,
+            })
+        }
+        method => panic!("Unexpected call to {}", method),
+    }
+}
+
+fn parse_hash(hash: &Value) -> [u8; 32] {
+    let mut bytes = [0; 32];
+    hex::decode_to_slice(hash.as_str().unwrap(), &mut bytes).unwrap();
+
+    bytes
+}
+
+/// The protobuf encoding of a `DataRequestOutput` that retrieves a URL through HTTP GET.
+pub fn witnet_request(url: &str) -> Vec<u8> {
+    // A `RADRetrieve` with an HTTP GET kind, the URL and a CBOR script
+    let mut retrieve = vec![1 << 3, 1];
+    retrieve.extend(protobuf_field(2, url.as_bytes()));
+    retrieve.extend(protobuf_field(3, &[0x80]));
+    // A `DataRequestOutput` with a `RADRequest` and a witness reward
+    let mut request = protobuf_field(1, &protobuf_field(2, &retrieve));
+    request.extend([2 << 3, 0xe8, 0x07]);
+
+    request
+}
+
+/// The JSON form of `witnet_request(url)`, as Witnet nodes take it in `sendRequest`.
+pub fn witnet_request_dro(url: &str) -> Value {
+    json!({
+        "data_request": {
+            "time_lock": 0,
+            "retrieve": [{
+                "kind": "HTTP-GET",
+                "url": url,
+                "script": [128],
+                "body": [],
+                "headers": [],
+            }],
+            "aggregate": { "filters": [], "reducer": 0 },
+            "tally": { "filters": [], "reducer": 0 },
+        },
+        "witness_reward": 1000,
+        "witnesses": 0,
+        "commit_and_reveal_fee": 0,
+        "min_consensus_percentage": 0,
+        "collateral": 0,
+    })
+}
+
+/// A length delimited protobuf field, for payloads shorter than 128 bytes.
+fn protobuf_field(number: u8, payload: &[u8]) -> Vec<u8> {
+    let mut field = vec![number << 3 | 2, payload.len() as u8];
+    field.extend_from_slice(payload);
+
+    field
+}
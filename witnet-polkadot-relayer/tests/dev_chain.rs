@@ -0,0 +1,120 @@
+//! End to end tests against a dev chain that includes the pallet as `Witnet`, with the account of
+//! Alice as an operator, as in the genesis config suggested by the README of the pallet.
+//!
+//! Start such a chain with `--dev`, then run `cargo test -- --ignored`. The URL of the chain can be
+//! set through `WITNET_RELAYER_DEV_CHAIN_URL`.
+
+mod common;
+
+use std::{
+    future::Future,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use subxt::{
+    dynamic::Value,
+    ext::sp_core::{sr25519, Pair},
+    tx::PairSigner,
+    OnlineClient, PolkadotConfig,
+};
+use witnet_polkadot_relayer::{
+    chain::Chain,
+    config::{ChainConfig, Config, WitnetConfig},
+    relayer::Relayer,
+};
+
+use common::{witnet_request, witnet_request_dro, MockWitnetNode};
+
+const DEFAULT_DEV_CHAIN_URL: &str = "ws://127.0.0.1:9944";
+
+fn dev_chain_url() -> String {
+    std::env::var("WITNET_RELAYER_DEV_CHAIN_URL").unwrap_or_else(|_| DEFAULT_DEV_CHAIN_URL.into())
+}
+
+/// Post a request as Bob, and wait for it to be finalized.
+async fn post_request(bytes: Vec<u8>) {
+    let api = OnlineClient::<PolkadotConfig>::from_url(dev_chain_url())
+        .await
+        .unwrap();
+    let signer = PairSigner::new(sr25519::Pair::from_string("//Bob", None).unwrap());
+    let call = subxt::dynamic::tx(
+        "Witnet",
+        "post_request",
+        vec![Value::u128(1_000), Value::from_bytes(bytes)],
+    );
+
+    api.tx()
+        .sign_and_submit_then_watch_default(&call, &signer)
+        .await
+        .unwrap()
+        .wait_for_finalized_success()
+        .await
+        .unwrap();
+}
+
+/// Wait for a condition to hold, for up to a minute.
+async fn wait_for<F: Future<Output = bool>>(mut condition: impl FnMut() -> F) {
+    tokio::time::timeout(Duration::from_secs(60), async {
+        while !condition().await {
+            tokio::time::sleep(Duration::from_millis(500)).await;
+        }
+    })
+    .await
+    .expect("Timed out waiting for the relayer");
+}
+
+#[tokio::test]
+#[ignore = "requires a dev chain"]
+async fn test_relay_request() {
+    let node = MockWitnetNode::start().await;
+    let state_dir = tempfile::tempdir().unwrap();
+    let chain_config = ChainConfig {
+        url: dev_chain_url(),
+        operator_seed: "//Alice".into(),
+    };
+    let chain = Chain::connect(&chain_config).await.unwrap();
+
+    let pending_before = chain.pending_requests().await.unwrap();
+    let bytes = witnet_request("https://api.example.com");
+    post_request(bytes.clone()).await;
+    let request_id = chain
+        .pending_requests()
+        .await
+        .unwrap()
+        .into_iter()
+        .find(|request_id| !pending_before.contains(request_id))
+        .unwrap();
+
+    // Checkpoints start right after the request was posted, so that the tally is timestamped
+    // later than the request, but earlier than the report
+    tokio::time::sleep(Duration::from_secs(1)).await;
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+    let config = Config {
+        state_path: state_dir.path().join("state.json"),
+        chain: chain_config,
+        witnet: WitnetConfig {
+            node_address: node.address.to_string(),
+            fee: 1,
+            poll_interval: 1,
+            checkpoint_zero_timestamp: now.as_secs(),
+            checkpoints_period: 1,
+        },
+    };
+    let relayer = tokio::spawn(Relayer::new(config).await.unwrap().run());
+
+    // The request gets sent to Witnet
+    let node_ref = &node;
+    wait_for(move || async move { !node_ref.requests().is_empty() }).await;
+    let (dr_tx_hash, dro, _) = node.requests()[0].clone();
+    assert_eq!(dro, witnet_request_dro("https://api.example.com"));
+
+    // Its result is only reported once the tally is final
+    node.resolve(dr_tx_hash, vec![24, 42], 0);
+    tokio::time::sleep(Duration::from_secs(3)).await;
+    assert_eq!(chain.request_bytes(request_id).await.unwrap(), Some(bytes));
+    node.confirm(dr_tx_hash);
+    let chain = &chain;
+    wait_for(move || async move { chain.request_bytes(request_id).await.unwrap().is_none() }).await;
+
+    relayer.abort();
+}
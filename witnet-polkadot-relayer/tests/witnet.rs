@@ -0,0 +1,89 @@
+mod common;
+
+use std::path::Path;
+
+use witnet_polkadot_relayer::{
+    config::Config,
+    state::{RequestState, State},
+    witnet::{Tally, WitnetClient},
+};
+
+use common::{witnet_request, witnet_request_dro, MockWitnetNode};
+
+#[tokio::test]
+async fn test_witnet_client() {
+    let node = MockWitnetNode::start().await;
+    let client = WitnetClient::new(node.address.to_string());
+
+    let bytes = witnet_request("https://api.example.com");
+    let dr_tx_hash = client.send_request(&bytes, 10).await.unwrap();
+    let dro = witnet_request_dro("https://api.example.com");
+    assert_eq!(node.requests(), vec![(dr_tx_hash, dro, 10)]);
+
+    // Requests that are not valid data request outputs never reach the node
+    assert!(client.send_request(&[1, 2, 3], 10).await.is_err());
+    assert_eq!(node.requests().len(), 1);
+
+    // There is no tally until the data request gets resolved
+    assert_eq!(client.data_request_tally(&dr_tx_hash).await.unwrap(), None);
+    node.resolve(dr_tx_hash, vec![24, 42], 100);
+    let tally = client.data_request_tally(&dr_tx_hash).await.unwrap();
+    let block_hash = [100; 32];
+    assert_eq!(
+        tally,
+        Some(Tally {
+            result_bytes: vec![24, 42],
+            block_hash,
+        })
+    );
+
+    // The checkpoint of the block that includes the tally is only known once it is final
+    let checkpoint = client.confirmed_checkpoint(&block_hash).await.unwrap();
+    assert_eq!(checkpoint, None);
+    node.confirm(dr_tx_hash);
+    let checkpoint = client.confirmed_checkpoint(&block_hash).await.unwrap();
+    assert_eq!(checkpoint, Some(100));
+}
+
+#[test]
+fn test_state() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("state.json");
+
+    // A missing state file means that there are no requests yet
+    let mut state = State::load(&path).unwrap();
+    assert!(state.requests().is_empty());
+    state.insert(0, RequestState::New).unwrap();
+    state
+        .insert(
+            1,
+            RequestState::Sent {
+                dr_tx_hash: [7; 32],
+            },
+        )
+        .unwrap();
+    state.remove(0).unwrap();
+
+    // Every change is persisted
+    let state = State::load(&path).unwrap();
+    let expected = vec![(
+        1,
+        RequestState::Sent {
+            dr_tx_hash: [7; 32],
+        },
+    )];
+    assert_eq!(state.requests(), expected);
+}
+
+#[test]
+fn test_example_config() {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("relayer.example.toml");
+    let config = Config::from_file(&path).unwrap();
+
+    assert_eq!(config.witnet.node_address, "127.0.0.1:21338");
+    // Checkpoint timestamps are given in milliseconds, as the pallet expects
+    let timestamp = config.witnet.checkpoint_timestamp(2);
+    assert_eq!(timestamp, 1_602_666_090_000);
+    // Checkpoints too far in the future do not overflow
+    assert_eq!(config.witnet.checkpoint_timestamp(u64::MAX), u64::MAX);
+}